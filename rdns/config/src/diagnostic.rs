@@ -0,0 +1,126 @@
+use rdns_core::error::LineCharPos;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    /// The zone is invalid and should not be served
+    Error,
+    /// The zone can be served but is likely to behave differently to what was intended
+    Warning,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A problem found in a zone file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    /// Where in the zone file the problem is, if it relates to a particular record
+    pub position: Option<LineCharPos>,
+    /// The file the position is in, when the zone was read from files
+    pub file: Option<PathBuf>,
+    /// The text of the line the position is on, when it is known
+    pub source_line: Option<String>,
+    /// The `$INCLUDE` directives which led to the file, innermost first
+    pub included_from: Vec<IncludeSite>,
+}
+
+/// Where a file was included from by an `$INCLUDE` directive
+#[derive(Debug, Clone, PartialEq)]
+pub struct IncludeSite {
+    /// The including file, when the zone was read from files
+    pub file: Option<PathBuf>,
+    pub position: LineCharPos,
+}
+
+impl Diagnostic {
+    pub fn error(message: String, position: Option<LineCharPos>) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            message,
+            position,
+            file: None,
+            source_line: None,
+            included_from: Vec::new(),
+        }
+    }
+
+    pub fn warning(message: String, position: Option<LineCharPos>) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            message,
+            position,
+            file: None,
+            source_line: None,
+            included_from: Vec::new(),
+        }
+    }
+
+    /// Sets the file the diagnostic's position is in
+    pub fn in_file(mut self, file: Option<PathBuf>) -> Self {
+        self.file = file;
+        self
+    }
+
+    /// Records that the diagnostic's file was included by a directive in another file. Sites are
+    /// added from the innermost include outwards.
+    pub fn included_from(mut self, file: Option<PathBuf>, position: LineCharPos) -> Self {
+        self.included_from.push(IncludeSite { file, position });
+        self
+    }
+
+    /// Sets the line the diagnostic's position is on, so that it can be shown with a caret
+    /// pointing at the problem
+    pub fn with_source_line(mut self, line: String) -> Self {
+        self.source_line = Some(line);
+        self
+    }
+}
+
+/// Formats the diagnostic as `file:line:char: severity: message`, followed by the source line with a
+/// caret under the position when the line is known, and then the chain of `$INCLUDE` directives
+/// which led to the file
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (&self.file, &self.position) {
+            (Some(file), Some(position)) => write!(f, "{}:{}: ", file.display(), position)?,
+            (Some(file), None) => write!(f, "{}: ", file.display())?,
+            (None, Some(position)) => write!(f, "{}: ", position)?,
+            (None, None) => {}
+        }
+        write!(f, "{}: {}", self.severity, self.message)?;
+
+        if let (Some(line), Some(position)) = (&self.source_line, &self.position) {
+            // The position counts bytes, and tabs are kept so that the caret lines up however wide
+            // they are shown
+            let bytes = line.as_bytes();
+            let end = (position.char.saturating_sub(1) as usize).min(bytes.len());
+            let before = String::from_utf8_lossy(&bytes[..end])
+                .chars()
+                .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+                .collect::<String>();
+            let gutter = " ".repeat(position.line.to_string().len());
+
+            write!(f, "\n{} | {}", position.line, line)?;
+            write!(f, "\n{} | {}^", gutter, before)?;
+        }
+
+        for site in &self.included_from {
+            match &site.file {
+                Some(file) => write!(f, "\n  included from {}:{}", file.display(), site.position)?,
+                None => write!(f, "\n  included from {}", site.position)?,
+            }
+        }
+
+        Ok(())
+    }
+}
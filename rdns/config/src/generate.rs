@@ -0,0 +1,249 @@
+//! Expansion of the BIND `$GENERATE start-stop[/step] lhs [ttl] [class] type rhs` directive,
+//! which creates one record for each value in the range. In the templates `$` is replaced by the
+//! value, `${offset,width,base}` by the value with an offset added, formatted in the given base and
+//! zero padded to the given width. An escaped `\$` is not substituted and keeps its backslash, so
+//! the record parser reads it as a literal `$`.
+
+/// The most records a single `$GENERATE` may create, which stops a typo in the range from
+/// producing millions of records
+pub const MAX_GENERATED_RECORDS: u64 = 65536;
+
+#[derive(Debug, PartialEq)]
+pub struct GenerateRange {
+    start: u32,
+    stop: u32,
+    step: u32,
+}
+
+impl GenerateRange {
+    /// Parses a range written as `start-stop` or `start-stop/step`
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let invalid = || format!("invalid $GENERATE range [{}]", text);
+
+        let (range, step) = match text.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().map_err(|_| invalid())?),
+            None => (text, 1),
+        };
+        let (start, stop) = range.split_once('-').ok_or_else(invalid)?;
+        let start = start.parse::<u32>().map_err(|_| invalid())?;
+        let stop = stop.parse::<u32>().map_err(|_| invalid())?;
+
+        if step == 0 {
+            return Err(format!("the $GENERATE step must not be zero in [{}]", text));
+        }
+        if start > stop {
+            return Err(format!(
+                "the $GENERATE range [{}] starts after it stops",
+                text
+            ));
+        }
+
+        let range = GenerateRange { start, stop, step };
+        if range.len() > MAX_GENERATED_RECORDS {
+            return Err(format!(
+                "the $GENERATE range [{}] would create {} records, more than the limit of {}",
+                text,
+                range.len(),
+                MAX_GENERATED_RECORDS
+            ));
+        }
+
+        Ok(range)
+    }
+
+    pub fn len(&self) -> u64 {
+        ((self.stop - self.start) / self.step) as u64 + 1
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = u32> {
+        (self.start..=self.stop).step_by(self.step as usize)
+    }
+}
+
+/// Substitutes the value into a template
+pub fn expand(template: &str, value: u32) -> Result<String, String> {
+    let mut result = String::new();
+    let mut chars = template.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => {
+                result.push('\\');
+                if let Some(escaped) = chars.next() {
+                    result.push(escaped);
+                }
+            }
+            '$' if chars.peek() == Some(&'{') => {
+                chars.next();
+                let mut modifiers = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(ch) => modifiers.push(ch),
+                        None => {
+                            return Err(format!(
+                                "unterminated $GENERATE modifier in [{}]",
+                                template
+                            ))
+                        }
+                    }
+                }
+                result.push_str(&format_modified(&modifiers, value)?);
+            }
+            '$' => result.push_str(&value.to_string()),
+            _ => result.push(ch),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Formats the value for a `${offset,width,base}` modifier, where the width and base are optional
+fn format_modified(modifiers: &str, value: u32) -> Result<String, String> {
+    let invalid = || format!("invalid $GENERATE modifier [${{{}}}]", modifiers);
+
+    let mut parts = modifiers.split(',');
+    let offset = match parts.next() {
+        Some("") | None => 0,
+        Some(offset) => offset.trim().parse::<i64>().map_err(|_| invalid())?,
+    };
+    let width = match parts.next() {
+        Some(width) => width.trim().parse::<usize>().map_err(|_| invalid())?,
+        None => 0,
+    };
+    let base = match parts.next() {
+        Some(base) => base.trim(),
+        None => "d",
+    };
+    if parts.next().is_some() {
+        return Err(invalid());
+    }
+
+    let value = u32::try_from(value as i64 + offset).map_err(|_| {
+        format!(
+            "the $GENERATE modifier [${{{}}}] gives {} which is out of range",
+            modifiers,
+            value as i64 + offset
+        )
+    })?;
+
+    match base {
+        "d" => Ok(format!("{:0width$}", value, width = width)),
+        "o" => Ok(format!("{:0width$o}", value, width = width)),
+        "x" => Ok(format!("{:0width$x}", value, width = width)),
+        "X" => Ok(format!("{:0width$X}", value, width = width)),
+        "n" => Ok(nibbles(value, width, false)),
+        "N" => Ok(nibbles(value, width, true)),
+        _ => Err(invalid()),
+    }
+}
+
+/// Writes the value as hex digits in reverse order separated by dots, as used for `ip6.arpa`
+/// names. The width counts the dots, so a width of 7 gives four nibbles such as `a.0.0.0`.
+fn nibbles(mut value: u32, mut width: usize, uppercase: bool) -> String {
+    let mut result = String::new();
+
+    loop {
+        let nibble = std::char::from_digit(value & 0xf, 16).unwrap();
+        result.push(if uppercase {
+            nibble.to_ascii_uppercase()
+        } else {
+            nibble
+        });
+        value >>= 4;
+        width = width.saturating_sub(1);
+
+        if value == 0 && width == 0 {
+            return result;
+        }
+
+        // A name can't end with a separator, so a width which does gets one more nibble
+        result.push('.');
+        width = width.saturating_sub(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generate::{expand, GenerateRange, MAX_GENERATED_RECORDS};
+
+    #[test]
+    fn parse_range() {
+        let range = GenerateRange::parse("1-10").unwrap();
+        assert_eq!(10, range.len());
+        assert_eq!(
+            vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
+            range.values().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn parse_range_with_step() {
+        let range = GenerateRange::parse("0-10/4").unwrap();
+        assert_eq!(3, range.len());
+        assert_eq!(vec![0, 4, 8], range.values().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn parse_invalid_ranges() {
+        assert!(GenerateRange::parse("10").is_err());
+        assert!(GenerateRange::parse("a-b").is_err());
+        assert!(GenerateRange::parse("10-1").is_err());
+        assert!(GenerateRange::parse("1-10/0").is_err());
+        assert!(GenerateRange::parse("-1-10").is_err());
+    }
+
+    #[test]
+    fn parse_range_over_limit() {
+        assert!(GenerateRange::parse(&format!("1-{}", MAX_GENERATED_RECORDS)).is_ok());
+        assert!(GenerateRange::parse(&format!("0-{}", MAX_GENERATED_RECORDS)).is_err());
+        assert!(GenerateRange::parse("0-4294967295").is_err());
+        assert!(GenerateRange::parse("0-4294967295/65536").is_ok());
+    }
+
+    #[test]
+    fn expand_value() {
+        assert_eq!("host-7 A 10.0.0.7", expand("host-$ A 10.0.0.$", 7).unwrap());
+    }
+
+    #[test]
+    fn expand_escaped_dollar() {
+        assert_eq!("h\\$1", expand("h\\$$", 1).unwrap());
+        assert_eq!("cost\\.\\$7", expand("cost\\.\\$$", 7).unwrap());
+    }
+
+    #[test]
+    fn expand_with_offset_and_width() {
+        assert_eq!("host17", expand("host${10}", 7).unwrap());
+        assert_eq!("host2", expand("host${-5}", 7).unwrap());
+        assert_eq!("host007", expand("host${0,3}", 7).unwrap());
+        assert_eq!("host7", expand("host${}", 7).unwrap());
+    }
+
+    #[test]
+    fn expand_with_bases() {
+        assert_eq!("0377", expand("${0,4,o}", 255).unwrap());
+        assert_eq!("00ff", expand("${0,4,x}", 255).unwrap());
+        assert_eq!("00FF", expand("${0,4,X}", 255).unwrap());
+        assert_eq!("255", expand("${0,0,d}", 255).unwrap());
+    }
+
+    #[test]
+    fn expand_nibbles() {
+        assert_eq!("f.f", expand("${0,0,n}", 255).unwrap());
+        assert_eq!("F.F", expand("${0,0,N}", 255).unwrap());
+        assert_eq!("0", expand("${0,0,n}", 0).unwrap());
+        assert_eq!("a.0.0.0", expand("${0,7,n}", 10).unwrap());
+        assert_eq!("a.0.0.0.0", expand("${0,8,n}", 10).unwrap());
+        assert_eq!("4.3.2.1", expand("${0,3,n}", 0x1234).unwrap());
+    }
+
+    #[test]
+    fn expand_invalid_modifiers() {
+        assert!(expand("${1,2,q}", 1).is_err());
+        assert!(expand("${a}", 1).is_err());
+        assert!(expand("${1,2,d,4}", 1).is_err());
+        assert!(expand("${1,2", 1).is_err());
+        assert!(expand("${-2}", 1).is_err());
+    }
+}
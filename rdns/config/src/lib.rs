@@ -1,3 +1,8 @@
+mod diagnostic;
+mod generate;
 mod txt_config;
+mod validate;
 
-pub use txt_config::load_txt_config;
+pub use diagnostic::{Diagnostic, Severity};
+pub use txt_config::{load_txt_config, load_txt_zone, TxtZone, TxtZoneParser, ZoneEntry};
+pub use validate::validate_zone;
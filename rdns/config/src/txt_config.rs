@@ -1,20 +1,145 @@
+use crate::diagnostic::{Diagnostic, Severity};
 use rdns_core::error::{LineCharPos, RDNSError};
 use std::fs::File;
 use std::io;
 use std::io::{BufRead, BufReader, Lines, Read};
 use std::iter::Peekable;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::vec::IntoIter;
 
 pub fn load_txt_config<P>(path: P) -> Result<Vec<rdns_core::ResourceRecord>, RDNSError>
 where
     P: AsRef<Path>,
 {
-    let mut lines = read_lines(path)?;
+    let zone = load_txt_zone(path)?;
+    if let Some(error) = zone.first_error() {
+        return Err(error);
+    }
+
+    Ok(zone.entries.into_iter().map(|entry| entry.record).collect())
+}
+
+/// Loads the records from a zone file along with where each was found, so that problems with
+/// them can be reported against the file
+pub fn load_txt_zone<P>(path: P) -> Result<TxtZone, RDNSError>
+where
+    P: AsRef<Path>,
+{
+    TxtZoneParser::new().parse_file(path)
+}
+
+/// Parses zones written in the master file format (RFC 1035 section 5) from files or from memory
+#[derive(Debug, Clone)]
+pub struct TxtZoneParser {
+    origin: rdns_core::name::Name,
+    class: Option<rdns_core::RRClass<u16>>,
+}
+
+impl Default for TxtZoneParser {
+    fn default() -> Self {
+        TxtZoneParser {
+            origin: rdns_core::name::Name::root(),
+            class: None,
+        }
+    }
+}
+
+impl TxtZoneParser {
+    /// A parser which starts with the root as the origin and takes the class from the first record
+    pub fn new() -> Self {
+        TxtZoneParser::default()
+    }
+
+    /// Sets the origin which relative names are completed with, until a `$ORIGIN` changes it
+    pub fn with_origin(mut self, origin: rdns_core::name::Name) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// Sets the class of records which don't give one. Records of any other class are rejected.
+    pub fn with_class(mut self, class: rdns_core::RRClass<u16>) -> Self {
+        self.class = Some(class);
+        self
+    }
+
+    /// Parses a zone file. `$INCLUDE` file names are relative to the file's directory.
+    pub fn parse_file<P>(&self, path: P) -> Result<TxtZone, RDNSError>
+    where
+        P: AsRef<Path>,
+    {
+        let mut lines = read_lines(&path)?;
+
+        parser::parse_lines(
+            &mut lines,
+            self.origin.clone(),
+            self.class.clone(),
+            Some(path.as_ref()),
+        )
+    }
+
+    /// Parses a zone from any reader, such as stdin. `$INCLUDE` file names are relative to the
+    /// current directory.
+    pub fn parse_reader<R: BufRead>(&self, reader: R) -> Result<TxtZone, RDNSError> {
+        parser::parse_lines(
+            &mut reader.lines(),
+            self.origin.clone(),
+            self.class.clone(),
+            None,
+        )
+    }
+
+    pub fn parse_str(&self, zone: &str) -> Result<TxtZone, RDNSError> {
+        self.parse_reader(zone.as_bytes())
+    }
+
+    /// Parses a UTF-8 encoded zone. Lines which are not UTF-8 are reported as errors and skipped.
+    pub fn parse_bytes(&self, zone: &[u8]) -> Result<TxtZone, RDNSError> {
+        self.parse_reader(zone)
+    }
+}
+
+/// The contents of a zone file
+#[derive(Debug, Clone)]
+pub struct TxtZone {
+    /// The records which could be parsed, leaving out any with errors
+    pub entries: Vec<ZoneEntry>,
+    /// Errors in records which could not be parsed, and warnings about records which were accepted
+    /// but may not mean what was intended, in the order they were found
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl TxtZone {
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity == Severity::Error)
+    }
 
-    let records = parser::TxtConfigParser::parse(&mut lines, rdns_core::name::Name::root())?;
+    /// The first error found, for callers which stop at the first problem
+    pub fn first_error(&self) -> Option<RDNSError> {
+        self.diagnostics
+            .iter()
+            .find(|diagnostic| diagnostic.severity == Severity::Error)
+            .map(|diagnostic| {
+                RDNSError::MasterFileFormatError(
+                    diagnostic.message.clone(),
+                    diagnostic
+                        .position
+                        .clone()
+                        .unwrap_or(LineCharPos { line: 1, char: 1 }),
+                )
+            })
+    }
+}
 
-    Ok(records)
+/// A resource record read from a zone file
+#[derive(Debug, Clone)]
+pub struct ZoneEntry {
+    pub record: rdns_core::ResourceRecord,
+    /// Where the record starts in the zone file
+    pub position: LineCharPos,
+    /// The file the record was read from, which may have been included by the zone file
+    pub file: Option<PathBuf>,
 }
 
 fn read_lines<P>(path: P) -> io::Result<Lines<BufReader<File>>>
@@ -27,23 +152,21 @@ where
 
 struct ParserReader<'a, R: Read + BufRead> {
     lines: &'a mut Lines<R>,
+    /// The whole of the current line, for showing where problems are
+    text: String,
     line: Peekable<IntoIter<u8>>,
     line_num: u32,
     char_num: u32,
 }
 
 impl<'a, R: Read + BufRead> ParserReader<'a, R> {
+    /// A reader positioned before the first line, which [`ParserReader::next_line`] moves to
     fn new(lines: &'a mut Lines<R>) -> Self {
-        let line = if let Some(Ok(line)) = lines.next() {
-            line
-        } else {
-            "".to_string()
-        };
-
         ParserReader {
             lines,
-            line: line.into_bytes().into_iter().peekable(),
-            line_num: 1,
+            text: String::new(),
+            line: Vec::new().into_iter().peekable(),
+            line_num: 0,
             char_num: 1,
         }
     }
@@ -61,18 +184,33 @@ impl<'a, R: Read + BufRead> ParserReader<'a, R> {
         &mut self.line
     }
 
-    fn next_line(&mut self) -> bool {
-        let line = if let Some(Ok(line)) = self.lines.next() {
-            line
-        } else {
-            return false;
+    /// Moves to the next line, returning false at the end of the input. A line which is not UTF-8
+    /// is still moved to, but as an empty line, and reported as a format error at its position so
+    /// that parsing can carry on after it. Other errors reading the input are returned as they are.
+    fn next_line(&mut self) -> Result<bool, RDNSError> {
+        let line = match self.lines.next() {
+            Some(Ok(line)) => line,
+            Some(Err(error)) if error.kind() == io::ErrorKind::InvalidData => {
+                self.line_num += 1;
+                self.char_num = 1;
+                self.text = String::new();
+                self.line = Vec::new().into_iter().peekable();
+
+                return Err(RDNSError::MasterFileFormatError(
+                    "the line is not valid UTF-8".to_string(),
+                    self.current_position(),
+                ));
+            }
+            Some(Err(error)) => return Err(error.into()),
+            None => return Ok(false),
         };
 
         self.line_num += 1;
         self.char_num = 1;
+        self.text = line.clone();
         self.line = line.into_bytes().into_iter().peekable();
 
-        true
+        Ok(true)
     }
 
     fn current_position(&self) -> LineCharPos {
@@ -84,21 +222,93 @@ impl<'a, R: Read + BufRead> ParserReader<'a, R> {
 }
 
 mod parser {
-    use crate::txt_config::{read_lines, ParserReader};
+    use crate::diagnostic::Diagnostic;
+    use crate::generate::{expand, GenerateRange};
+    use crate::txt_config::{read_lines, ParserReader, TxtZone, ZoneEntry};
+    use rdns_core::error::LineCharPos;
     use rdns_core::error::RDNSError;
+    use rdns_core::wire::WireReader;
     use std::collections::HashSet;
-    use std::io::{BufRead, Lines, Read};
-    use std::net::Ipv4Addr;
-    use std::path::PathBuf;
-    use std::rc::Rc;
+    use std::io::{BufRead, Cursor, Lines, Read};
+    use std::net::{Ipv4Addr, Ipv6Addr};
+    use std::path::{Path, PathBuf};
     use std::str::FromStr;
 
+    /// How deeply `$INCLUDE` directives may be nested, which stops runaway includes
+    const MAX_INCLUDE_DEPTH: usize = 16;
+
+    /// Parses a zone, resolving any `$INCLUDE` file names relative to the file it was read from
+    pub fn parse_lines<R: Read + BufRead>(
+        lines: &mut Lines<R>,
+        origin: rdns_core::name::Name,
+        class: Option<rdns_core::RRClass<u16>>,
+        file: Option<&Path>,
+    ) -> Result<TxtZone, RDNSError> {
+        let mut parser = TxtConfigParser::new(lines, origin);
+        parser.current_class = class;
+        if let Some(path) = file {
+            parser.file = Some(path.to_path_buf());
+            parser.include_chain = vec![path.canonicalize()?];
+        }
+        parser.parse_entries()
+    }
+
+    /// A message for an error and the position it happened at, if the error includes one
+    fn describe_error(error: &RDNSError) -> (String, Option<LineCharPos>) {
+        match error {
+            RDNSError::MasterFileFormatError(message, position) => {
+                (message.clone(), Some(position.clone()))
+            }
+            RDNSError::IncludeFailed {
+                file,
+                position,
+                source,
+            } => (
+                format!("could not include {} - {}", file, describe_error(source).0),
+                Some(position.clone()),
+            ),
+            error => {
+                let mut message = error.to_string();
+                let mut source = std::error::Error::source(error);
+                while let Some(error) = source {
+                    message.push_str(&format!(": {}", error));
+                    source = error.source();
+                }
+                (message, None)
+            }
+        }
+    }
+
+    /// A TTL which records without their own fall back to, with the line which set it
+    #[derive(Clone)]
+    struct TtlSource<T> {
+        ttl: T,
+        line: u32,
+        file: Option<PathBuf>,
+    }
+
     pub struct TxtConfigParser<'a, R: Read + BufRead> {
         state: ParserReader<'a, R>,
         current_origin: rdns_core::name::Name,
         current_name: Option<rdns_core::name::Name>,
-        current_class: rdns_core::RRClass<u16>,
+        current_class: Option<rdns_core::RRClass<u16>>,
+        /// The TTL set by `$TTL` for records which don't give their own (RFC 2308 section 4)
+        default_ttl: Option<i32>,
+        /// The TTL of the last record which gave one explicitly
+        last_ttl: Option<TtlSource<i32>>,
+        /// The MINIMUM field of the SOA record, once it has been read
+        soa_minimum: Option<TtlSource<u32>>,
+        /// The TTL fall backs which have already been warned about
+        ttl_warnings: HashSet<String>,
+        /// Where the record currently being parsed starts
+        record_position: LineCharPos,
+        entries: Vec<ZoneEntry>,
+        diagnostics: Vec<Diagnostic>,
         multiline: bool,
+        /// The file being parsed, if the input is a file
+        file: Option<PathBuf>,
+        /// The canonical paths of this file and the files which included it, outermost first
+        include_chain: Vec<PathBuf>,
     }
 
     impl<'a, R: Read + BufRead> TxtConfigParser<'a, R> {
@@ -107,59 +317,152 @@ mod parser {
                 state: ParserReader::new(lines),
                 current_origin: origin,
                 current_name: None,
-                current_class: rdns_core::RRClass::UNKNOWN(0),
+                current_class: None,
+                default_ttl: None,
+                last_ttl: None,
+                soa_minimum: None,
+                ttl_warnings: HashSet::new(),
+                record_position: LineCharPos { line: 1, char: 1 },
+                entries: Vec::new(),
+                diagnostics: Vec::new(),
                 multiline: false,
+                file: None,
+                include_chain: Vec::new(),
             }
         }
 
+        #[cfg(test)]
         pub fn parse(
             lines: &'a mut Lines<R>,
             origin: rdns_core::name::Name,
         ) -> Result<Vec<rdns_core::ResourceRecord>, RDNSError> {
-            let mut parser = TxtConfigParser::new(lines, origin);
+            let zone = TxtConfigParser::parse_zone(lines, origin)?;
+            if let Some(error) = zone.first_error() {
+                return Err(error);
+            }
 
-            let mut records = Vec::new();
-            let mut start_of_line: bool;
+            Ok(zone.entries.into_iter().map(|entry| entry.record).collect())
+        }
 
-            println!("origin is {}", parser.current_origin);
+        #[cfg(test)]
+        pub fn parse_zone(
+            lines: &'a mut Lines<R>,
+            origin: rdns_core::name::Name,
+        ) -> Result<TxtZone, RDNSError> {
+            TxtConfigParser::new(lines, origin).parse_entries()
+        }
 
-            'lines: loop {
-                start_of_line = true;
+        fn parse_entries(mut self) -> Result<TxtZone, RDNSError> {
+            let span = tracing::debug_span!(
+                "zone",
+                file = ?self.file,
+                origin = %self.current_origin
+            );
+            let _entered = span.enter();
 
-                while let Some(ch) = parser.state.peek_char() {
-                    match ch {
-                        b' ' | b'\t' => {
-                            parser.state.next_char();
-                            start_of_line = false;
-                        }
-                        b';' => break,
-                        b'$' => parser.parse_control_entry()?,
-                        _ => {
-                            let rr = if start_of_line {
-                                parser.parse_name_and_rr()?
-                            } else {
-                                parser.parse_rr()?
-                            };
-                            records.push(rr);
-                            break;
-                        }
-                    };
+            loop {
+                match self.state.next_line() {
+                    Ok(true) => {}
+                    Ok(false) => break,
+                    // A line which can't be read is skipped like a line with a bad record
+                    Err(error @ RDNSError::MasterFileFormatError(..)) => {
+                        self.recover(error);
+                        continue;
+                    }
+                    Err(error) => return Err(error),
                 }
 
-                if !parser.state.next_line() {
-                    break 'lines;
+                if let Err(error) = self.parse_line() {
+                    self.recover(error);
                 }
             }
 
-            println!(
-                "final root is {}",
-                <rdns_core::name::Name as Into<String>>::into(parser.current_origin.clone())
+            tracing::debug!(
+                records = self.entries.len(),
+                diagnostics = self.diagnostics.len(),
+                "parsed zone"
             );
 
-            Ok(records)
+            Ok(TxtZone {
+                entries: self.entries,
+                diagnostics: self.diagnostics,
+            })
+        }
+
+        /// Parses the entry starting on the current line, which may continue onto later lines
+        fn parse_line(&mut self) -> Result<(), RDNSError> {
+            let mut start_of_line = true;
+
+            while let Some(ch) = self.state.peek_char() {
+                match ch {
+                    b' ' | b'\t' => {
+                        self.state.next_char();
+                        start_of_line = false;
+                    }
+                    b';' => break,
+                    b'$' => self.parse_control_entry()?,
+                    _ => {
+                        let position = self.state.current_position();
+                        self.record_position = position.clone();
+                        // Each record starts outside a block, so an error only skips a block
+                        // opened by the record that failed
+                        self.multiline = false;
+                        let record = if start_of_line {
+                            self.parse_name_and_rr()?
+                        } else {
+                            self.parse_rr()?
+                        };
+                        tracing::trace!(
+                            line = position.line,
+                            char = position.char,
+                            %record,
+                            "parsed record"
+                        );
+                        self.entries.push(ZoneEntry {
+                            record,
+                            position,
+                            file: self.file.clone(),
+                        });
+                        break;
+                    }
+                };
+            }
+
+            Ok(())
+        }
+
+        /// Records an error along with the line it is on, then skips the rest of the record so that
+        /// parsing can carry on with the next one
+        fn recover(&mut self, error: RDNSError) {
+            let (message, position) = describe_error(&error);
+            let position = position.unwrap_or_else(|| self.state.current_position());
+            tracing::debug!(line = position.line, char = position.char, %message, "skipping line");
+
+            // A line which could not be read has no text to show
+            let mut diagnostic =
+                Diagnostic::error(message, Some(position)).in_file(self.file.clone());
+            if !self.state.text.is_empty() {
+                diagnostic = diagnostic.with_source_line(self.state.text.clone());
+            }
+            self.diagnostics.push(diagnostic);
+
+            // The rest of a multi-line block belongs to the same record, so it is skipped too
+            while self.multiline {
+                match self.state.next_char() {
+                    Some(b')') => self.multiline = false,
+                    Some(_) => {}
+                    None => {
+                        if !self.state.next_line().unwrap_or(false) {
+                            self.multiline = false;
+                        }
+                    }
+                }
+            }
+            while self.state.next_char().is_some() {}
         }
 
         fn parse_control_entry(&mut self) -> Result<(), RDNSError> {
+            let position = self.state.current_position();
             self.state.next_char();
 
             let mut control_name = String::new();
@@ -180,18 +483,31 @@ mod parser {
                 "ORIGIN" => {
                     self.chomp();
                     let name = self.parse_domain_name()?;
+                    tracing::debug!(line = position.line, origin = %name, "changed origin");
                     self.current_origin = name;
                 }
+                "TTL" => {
+                    self.chomp();
+                    let ttl = self.parse_ttl()?;
+                    tracing::debug!(line = position.line, ttl, "changed default TTL");
+                    self.default_ttl = Some(ttl);
+                }
                 "INCLUDE" => {
                     let file_name = self.parse_file_name()?;
                     let domain_name = self
                         .maybe_parse_domain_name()?
                         .unwrap_or(self.current_origin.clone());
 
-                    let mut sub_lines = read_lines(file_name)?;
-
-                    // TODO capture result and push to current RRs
-                    TxtConfigParser::parse(&mut sub_lines, domain_name)?;
+                    tracing::debug!(
+                        line = position.line,
+                        file = %file_name.display(),
+                        origin = %domain_name,
+                        "including file"
+                    );
+                    self.include(file_name, domain_name, position)?;
+                }
+                "GENERATE" => {
+                    self.generate(position)?;
                 }
                 _ => {
                     return Err(RDNSError::MasterFileFormatError(
@@ -204,6 +520,175 @@ mod parser {
             Ok(())
         }
 
+        /// Parses an included file and merges its records, in order, with this file's. The included
+        /// file starts with the given origin and this file's class and TTL defaults, but changes it
+        /// makes to the origin and TTLs do not carry back (RFC 1035 section 5.1). Its diagnostics
+        /// record the directive so the chain of includes can be shown.
+        fn include(
+            &mut self,
+            file_name: PathBuf,
+            origin: rdns_core::name::Name,
+            position: LineCharPos,
+        ) -> Result<(), RDNSError> {
+            let path = match self.file.as_ref().and_then(|file| file.parent()) {
+                Some(directory) if file_name.is_relative() => directory.join(file_name),
+                _ => file_name,
+            };
+
+            let included =
+                self.parse_included_file(&path, origin, &position)
+                    .map_err(|source| RDNSError::IncludeFailed {
+                        file: path.display().to_string(),
+                        position: position.clone(),
+                        source: Box::new(source),
+                    })?;
+
+            // The class applies to the whole zone, so one first set by the included file holds
+            // for the rest of this file too
+            if self.current_class.is_none() {
+                self.current_class = included
+                    .entries
+                    .first()
+                    .map(|entry| entry.record.class.clone());
+            }
+
+            self.entries.extend(included.entries);
+            self.diagnostics.extend(
+                included.diagnostics.into_iter().map(|diagnostic| {
+                    diagnostic.included_from(self.file.clone(), position.clone())
+                }),
+            );
+
+            Ok(())
+        }
+
+        fn parse_included_file(
+            &self,
+            path: &Path,
+            origin: rdns_core::name::Name,
+            position: &LineCharPos,
+        ) -> Result<TxtZone, RDNSError> {
+            if self.include_chain.len() >= MAX_INCLUDE_DEPTH {
+                return Err(RDNSError::MasterFileFormatError(
+                    format!(
+                        "$INCLUDE is nested more than {} files deep",
+                        MAX_INCLUDE_DEPTH
+                    ),
+                    position.clone(),
+                ));
+            }
+
+            let canonical_path = path.canonicalize()?;
+            if self.include_chain.contains(&canonical_path) {
+                let chain = self
+                    .include_chain
+                    .iter()
+                    .chain([&canonical_path])
+                    .map(|file| file.display().to_string())
+                    .collect::<Vec<_>>();
+
+                return Err(RDNSError::MasterFileFormatError(
+                    format!("$INCLUDE creates a cycle {}", chain.join(" -> ")),
+                    position.clone(),
+                ));
+            }
+
+            let mut lines = read_lines(path)?;
+
+            let mut parser = TxtConfigParser::new(&mut lines, origin);
+            parser.current_class = self.current_class.clone();
+            parser.file = Some(path.to_path_buf());
+            parser.include_chain = self.include_chain.clone();
+            parser.include_chain.push(canonical_path);
+            parser.default_ttl = self.default_ttl;
+            parser.last_ttl = self.last_ttl.clone();
+            parser.soa_minimum = self.soa_minimum.clone();
+            parser.parse_entries()
+        }
+
+        /// Expands a `$GENERATE` directive, parsing the template after the range as a record for each
+        /// value in it. The records are all given the position of the directive.
+        fn generate(&mut self, position: LineCharPos) -> Result<(), RDNSError> {
+            self.chomp();
+            let range = GenerateRange::parse(&self.get_token())
+                .map_err(|message| RDNSError::MasterFileFormatError(message, position.clone()))?;
+            self.chomp();
+
+            let mut template = Vec::new();
+            while let Some(ch) = self.state.next_char() {
+                template.push(ch);
+            }
+            let template = String::from_utf8(template)?;
+
+            tracing::debug!(
+                line = position.line,
+                records = range.len(),
+                %template,
+                "generating records"
+            );
+
+            // The records are only added once every expansion has succeeded, so an error leaves
+            // none of them behind
+            let mut entries = Vec::new();
+            let mut diagnostics: Vec<Diagnostic> = Vec::new();
+            let mut current_class = self.current_class.clone();
+            let mut last_ttl = self.last_ttl.clone();
+            let mut ttl_warnings = self.ttl_warnings.clone();
+            for value in range.values() {
+                let line = expand(&template, value).map_err(|message| {
+                    RDNSError::MasterFileFormatError(message, position.clone())
+                })?;
+
+                let mut lines = Cursor::new(line.clone()).lines();
+                let mut parser = TxtConfigParser::new(&mut lines, self.current_origin.clone());
+                parser.state.next_line()?;
+                // A TTL given in the template is taken to be set on the directive's line
+                parser.record_position = position.clone();
+                parser.file = self.file.clone();
+                parser.current_class = current_class;
+                parser.default_ttl = self.default_ttl;
+                parser.last_ttl = last_ttl;
+                parser.soa_minimum = self.soa_minimum.clone();
+                parser.ttl_warnings = ttl_warnings;
+
+                let record = parser.parse_name_and_rr().map_err(|error| {
+                    RDNSError::MasterFileFormatError(
+                        format!("$GENERATE created an invalid record [{}] - {}", line, error),
+                        position.clone(),
+                    )
+                })?;
+
+                current_class = parser.current_class.clone();
+                last_ttl = parser.last_ttl;
+                ttl_warnings = parser.ttl_warnings;
+
+                // Report each problem once rather than for every generated record
+                for diagnostic in parser.diagnostics {
+                    if !diagnostics.iter().any(|d| d.message == diagnostic.message) {
+                        diagnostics.push(diagnostic);
+                    }
+                }
+
+                entries.push(ZoneEntry {
+                    record,
+                    position: position.clone(),
+                    file: self.file.clone(),
+                });
+            }
+
+            self.entries.append(&mut entries);
+            self.current_class = current_class;
+            self.last_ttl = last_ttl;
+            self.diagnostics
+                .extend(diagnostics.into_iter().map(|diagnostic| Diagnostic {
+                    position: Some(position.clone()),
+                    file: self.file.clone(),
+                    ..diagnostic
+                }));
+
+            Ok(())
+        }
+
         fn parse_name_and_rr(&mut self) -> Result<rdns_core::ResourceRecord, RDNSError> {
             self.current_name = Some(self.parse_domain_name()?);
 
@@ -212,102 +697,279 @@ mod parser {
         }
 
         fn parse_rr(&mut self) -> Result<rdns_core::ResourceRecord, RDNSError> {
+            // A record starting with a blank takes the owner of the previous record
+            let name = self.current_name.clone().ok_or_else(|| {
+                RDNSError::MasterFileFormatError(
+                    "record has no owner name and there is no previous owner".to_string(),
+                    self.record_position.clone(),
+                )
+            })?;
+
+            // The TTL and class are both optional and may appear in either order
             let mut ttl_opt = self.try_parse_ttl()?;
             self.chomp();
 
-            let text = self.get_text()?;
-            let mut class: rdns_core::RRClass<u16> = text.as_str().try_into().unwrap();
+            let mut text = self.get_token();
             self.chomp();
 
-            let mut rr_type: Option<rdns_core::RRType<u16>> =
-                if class == rdns_core::RRClass::UNKNOWN(0) {
-                    Some(text.as_str().try_into().unwrap())
-                } else {
-                    None
-                };
+            let mut class_opt = None;
+            if let Ok(class) = rdns_core::RRClass::try_from(text.as_str()) {
+                class_opt = Some(class);
 
-            // TODO constant for unknown
-            if rr_type == None || rr_type == Some(rdns_core::RRType::UNKNOWN(0)) {
-                if ttl_opt == None {
+                if ttl_opt.is_none() {
                     ttl_opt = self.try_parse_ttl()?;
                     self.chomp();
                 }
 
-                let text = self.get_text()?;
-                rr_type = Some(text.as_str().try_into().unwrap());
+                text = self.get_token();
                 self.chomp();
             }
 
-            if class == rdns_core::RRClass::UNKNOWN(0) {
-                if self.current_class != rdns_core::RRClass::UNKNOWN(0) {
-                    class = self.current_class.clone();
-                } else {
+            if text.is_empty() {
+                return Err(RDNSError::MasterFileFormatError(
+                    "missing resource record type".to_string(),
+                    self.state.current_position(),
+                ));
+            }
+
+            let rr_type = rdns_core::RRType::try_from(text.as_str()).map_err(|_| {
+                RDNSError::MasterFileFormatError(
+                    format!("unknown resource record type '{}'", text),
+                    self.state.current_position(),
+                )
+            })?;
+
+            let class = match (class_opt, &self.current_class) {
+                (Some(class), _) => class,
+                (None, Some(current_class)) => current_class.clone(),
+                (None, None) => {
                     return Err(RDNSError::MasterFileFormatError(
                         "No class".to_string(),
                         self.state.current_position(),
                     ));
                 }
-            } else if self.current_class == rdns_core::RRClass::UNKNOWN(0) {
-                self.current_class = class.clone();
+            };
+
+            match &self.current_class {
+                Some(current_class) if *current_class != class => {
+                    return Err(RDNSError::MasterFileFormatError(
+                        "File must only contain one class".to_string(),
+                        self.state.current_position(),
+                    ));
+                }
+                Some(_) => {}
+                None => self.current_class = Some(class.clone()),
             }
 
-            if class != self.current_class {
-                // TODO propagate to included files?
+            let rr_data = if self.is_generic_rdata() {
+                self.parse_generic_rdata(&rr_type)?
+            } else {
+                self.parse_rdata(&rr_type)?
+            };
+
+            // The record ends at the end of the line, or at the `)` closing its multi-line block
+            if self.more_rdata()? {
                 return Err(RDNSError::MasterFileFormatError(
-                    "File must only contain one class".to_string(),
+                    format!("unexpected data after the {} record data", rr_type),
                     self.state.current_position(),
                 ));
             }
 
-            let rr_data: Rc<dyn rdns_core::record::ResourceData> = match rr_type {
-                Some(rdns_core::RRType::A) => {
+            if let rdns_core::record::RData::SOA(soa) = &rr_data {
+                self.soa_minimum = Some(self.ttl_source(soa.minimum));
+            }
+
+            let ttl = match ttl_opt {
+                Some(ttl) => {
+                    self.last_ttl = Some(self.ttl_source(ttl));
+                    ttl
+                }
+                None => self.implicit_ttl(),
+            };
+
+            Ok(rdns_core::ResourceRecord {
+                name,
+                class,
+                ttl,
+                rdata: rr_data,
+            })
+        }
+
+        /// The TTL for a record which doesn't give one. This is the `$TTL` if there is one,
+        /// otherwise the TTL of the last record which gave one or else the SOA minimum (RFC 2308
+        /// section 4). Relying on the fall backs is warned about once for each line setting one,
+        /// as their meaning has changed.
+        fn implicit_ttl(&mut self) -> i32 {
+            if let Some(ttl) = self.default_ttl {
+                return ttl;
+            }
+
+            let (ttl, message) = match (&self.last_ttl, &self.soa_minimum) {
+                (Some(source), _) => (
+                    source.ttl,
+                    format!(
+                        "no TTL or $TTL given, using the previous TTL {} set on {}",
+                        source.ttl,
+                        self.describe_line(source)
+                    ),
+                ),
+                (None, Some(source)) => (
+                    source.ttl as i32,
+                    format!(
+                        "no TTL or $TTL given, using the SOA minimum {} set on {}",
+                        source.ttl,
+                        self.describe_line(source)
+                    ),
+                ),
+                (None, None) => (
+                    0,
+                    "no TTL or $TTL given, using 0 so the record will not be cached".to_string(),
+                ),
+            };
+
+            if self.ttl_warnings.insert(message.clone()) {
+                self.diagnostics.push(
+                    Diagnostic::warning(message, Some(self.record_position.clone()))
+                        .in_file(self.file.clone()),
+                );
+            }
+
+            ttl
+        }
+
+        fn ttl_source<T>(&self, ttl: T) -> TtlSource<T> {
+            TtlSource {
+                ttl,
+                line: self.record_position.line,
+                file: self.file.clone(),
+            }
+        }
+
+        /// Names the line a TTL was set on, with its file if that was a different one
+        fn describe_line<T>(&self, source: &TtlSource<T>) -> String {
+            match &source.file {
+                Some(file) if source.file != self.file => {
+                    format!("line {} of {}", source.line, file.display())
+                }
+                _ => format!("line {}", source.line),
+            }
+        }
+
+        fn parse_rdata(
+            &mut self,
+            rr_type: &rdns_core::RRType<u16>,
+        ) -> Result<rdns_core::record::RData, RDNSError> {
+            let rr_data = match rr_type {
+                rdns_core::RRType::A => {
                     let ip_address = self.parse_ip_addr()?;
-                    Rc::new(rdns_core::record::AliasResourceData(ip_address))
+                    rdns_core::record::RData::A(rdns_core::record::AliasResourceData(ip_address))
                 }
-                Some(rdns_core::RRType::NS) => {
+                rdns_core::RRType::AAAA => {
+                    let ip_address = self.parse_ipv6_addr()?;
+                    rdns_core::record::RData::AAAA(rdns_core::record::Ipv6AddressResourceData(
+                        ip_address,
+                    ))
+                }
+                rdns_core::RRType::NS => {
                     let name = self.parse_domain_name()?;
-                    Rc::new(rdns_core::record::NameServerResourceData(name))
+                    rdns_core::record::RData::NS(rdns_core::record::NameServerResourceData(name))
                 }
-                Some(rdns_core::RRType::CNAME) => {
+                rdns_core::RRType::CNAME => {
                     let name = self.parse_domain_name()?;
-                    Rc::new(rdns_core::record::CNameResourceData(name))
+                    rdns_core::record::RData::CNAME(rdns_core::record::CNameResourceData(name))
                 }
-                Some(rdns_core::RRType::SOA) => Rc::new(self.parse_soa()?),
-                Some(rdns_core::RRType::PTR) => {
+                rdns_core::RRType::SOA => rdns_core::record::RData::SOA(self.parse_soa()?),
+                rdns_core::RRType::PTR => {
                     let name = self.parse_domain_name()?;
-                    Rc::new(rdns_core::record::PointerResourceData(name))
+                    rdns_core::record::RData::PTR(rdns_core::record::PointerResourceData(name))
                 }
-                Some(rdns_core::RRType::MX) => {
+                rdns_core::RRType::MX => {
                     let preference = self.parse_number::<u16>()?;
                     self.chomp();
                     let exchange = self.parse_domain_name()?;
 
-                    Rc::new(rdns_core::record::MailExchangeResourceData {
+                    rdns_core::record::RData::MX(rdns_core::record::MailExchangeResourceData {
                         preference,
                         exchange,
                     })
                 }
-                Some(rr_type) => {
+                rdns_core::RRType::TXT => rdns_core::record::RData::TXT(self.parse_text()?),
+                rdns_core::RRType::MD => {
+                    let name = self.parse_domain_name()?;
+                    rdns_core::record::RData::MD(rdns_core::record::MailDestinationResourceData(
+                        name,
+                    ))
+                }
+                rdns_core::RRType::MF => {
+                    let name = self.parse_domain_name()?;
+                    rdns_core::record::RData::MF(rdns_core::record::MailForwarderResourceData(name))
+                }
+                rdns_core::RRType::MB => {
+                    let name = self.parse_domain_name()?;
+                    rdns_core::record::RData::MB(rdns_core::record::MailboxResourceData(name))
+                }
+                rdns_core::RRType::MG => {
+                    let name = self.parse_domain_name()?;
+                    rdns_core::record::RData::MG(rdns_core::record::MailGroupResourceData(name))
+                }
+                rdns_core::RRType::MR => {
+                    let name = self.parse_domain_name()?;
+                    rdns_core::record::RData::MR(rdns_core::record::MailRenameResourceData(name))
+                }
+                rdns_core::RRType::MINFO => {
+                    let responsible_mailbox = self.parse_domain_name()?;
+                    self.chomp();
+                    let error_mailbox = self.parse_domain_name()?;
+
+                    rdns_core::record::RData::MINFO(rdns_core::record::MailInfoResourceData {
+                        responsible_mailbox,
+                        error_mailbox,
+                    })
+                }
+                rdns_core::RRType::HINFO => {
+                    let cpu = self.parse_single_character_string()?;
+                    self.chomp();
+                    let os = self.parse_single_character_string()?;
+
+                    rdns_core::record::RData::HINFO(rdns_core::record::HInfoResourceData::new(
+                        cpu, os,
+                    )?)
+                }
+                rdns_core::RRType::WKS => rdns_core::record::RData::WKS(self.parse_wks()?),
+                rdns_core::RRType::NULL => {
                     return Err(RDNSError::MasterFileFormatError(
-                        format!("unknown resource record type '{:?}'", rr_type),
+                        "NULL records must use the \\# generic format".to_string(),
                         self.state.current_position(),
                     ));
                 }
-                None => {
+                rdns_core::RRType::SRV => {
+                    let priority = self.parse_number::<u16>()?;
+                    self.chomp();
+                    let weight = self.parse_number::<u16>()?;
+                    self.chomp();
+                    let port = self.parse_number::<u16>()?;
+                    self.chomp();
+                    let target = self.parse_domain_name()?;
+
+                    rdns_core::record::RData::SRV(rdns_core::record::ServiceResourceData {
+                        priority,
+                        weight,
+                        port,
+                        target,
+                    })
+                }
+                rdns_core::RRType::UNKNOWN(_) => {
                     return Err(RDNSError::MasterFileFormatError(
-                        format!("missing resource record type"),
+                        format!(
+                            "resource record type '{}' must use the \\# generic format",
+                            rr_type
+                        ),
                         self.state.current_position(),
                     ));
                 }
             };
 
-            Ok(rdns_core::ResourceRecord {
-                name: self.current_name.as_ref().unwrap().clone(),
-                rr_type: rr_type.unwrap(),
-                class,
-                ttl: ttl_opt.unwrap_or(0),
-                rdata: rr_data,
-            })
+            Ok(rr_data)
         }
 
         fn parse_soa(&mut self) -> Result<rdns_core::record::SOAResourceData, RDNSError> {
@@ -318,15 +980,15 @@ mod parser {
             self.parse_common_in_rr()?;
             let serial: u32 = self.parse_number()?;
             self.parse_common_in_rr()?;
-            let refresh: i32 = self.parse_number()?;
+            let refresh = self.parse_soa_timer("refresh")?;
             self.parse_common_in_rr()?;
-            let retry: i32 = self.parse_number()?;
+            let retry = self.parse_soa_timer("retry")?;
             self.parse_common_in_rr()?;
-            let expire: i32 = self.parse_number()?;
+            let expire = self.parse_soa_timer("expire")?;
             self.parse_common_in_rr()?;
-            let minimum: u32 = self.parse_number()?;
+            let minimum = self.parse_time_interval()?;
 
-            return Ok(rdns_core::record::SOAResourceData {
+            Ok(rdns_core::record::SOAResourceData {
                 primary_name,
                 responsible_name,
                 serial,
@@ -334,7 +996,7 @@ mod parser {
                 retry,
                 expire,
                 minimum,
-            });
+            })
         }
 
         fn parse_domain_name(&mut self) -> Result<rdns_core::name::Name, RDNSError> {
@@ -342,11 +1004,13 @@ mod parser {
                 self.state.next_char();
                 self.current_origin.clone()
             } else {
-                let result = rdns_core::name::Name::parse(
-                    &mut self.state.borrow(),
-                    HashSet::from([b' ', b'\t']),
-                );
-                match result {
+                let result = if self.is_unicode_name() {
+                    self.parse_unicode_name()
+                } else {
+                    rdns_core::name::Name::parse(self.state.borrow(), HashSet::from([b' ', b'\t']))
+                };
+                // Relative names are relative to the current origin (RFC 1035 section 5.1)
+                match result.and_then(|name| name.append(&self.current_origin)) {
                     Ok(name) => name,
                     Err(e) => {
                         return Err(RDNSError::MasterFileFormatError(
@@ -357,19 +1021,38 @@ mod parser {
                 }
             };
 
-            println!(
-                "found name {}",
-                <rdns_core::name::Name as Into<String>>::into(name.clone())
-            );
+            tracing::trace!(%name, "parsed name");
 
             Ok(name)
         }
 
+        /// Whether the next name contains any non-ASCII characters, in which case it is an
+        /// internationalized name to be converted to A-labels
+        fn is_unicode_name(&mut self) -> bool {
+            self.state
+                .borrow()
+                .clone()
+                .take_while(|&ch| !self.is_whitespace(ch) && !matches!(ch, b';' | b'(' | b')'))
+                .any(|ch| !ch.is_ascii())
+        }
+
+        fn parse_unicode_name(&mut self) -> Result<rdns_core::name::Name, RDNSError> {
+            let mut bytes = Vec::new();
+            while let Some(&ch) = self.state.peek_char() {
+                if self.is_whitespace(ch) || matches!(ch, b';' | b'(' | b')') {
+                    break;
+                }
+                bytes.push(self.state.next_char().unwrap());
+            }
+
+            rdns_core::name::Name::from_unicode(&String::from_utf8(bytes)?)
+        }
+
         fn maybe_parse_domain_name(&mut self) -> Result<Option<rdns_core::name::Name>, RDNSError> {
             self.chomp();
             match self.state.peek_char() {
                 Some(b';') | None => Ok(None),
-                Some(_) => self.parse_domain_name().map(|n| Some(n)),
+                Some(_) => self.parse_domain_name().map(Some),
             }
         }
 
@@ -381,14 +1064,15 @@ mod parser {
                 ));
             }
 
-            let mut path_builder = String::new();
-            while let Some(&ch) = self.state.peek_char() {
-                if !self.is_whitespace(ch) {
-                    path_builder.push(self.state.next_char().unwrap() as char)
-                }
+            let path = self.get_token();
+            if path.is_empty() {
+                return Err(RDNSError::MasterFileFormatError(
+                    "expected a file name".to_string(),
+                    self.state.current_position(),
+                ));
             }
 
-            Ok(path_builder.into())
+            Ok(path.into())
         }
 
         fn try_parse_ttl(&mut self) -> Result<Option<i32>, RDNSError> {
@@ -396,13 +1080,63 @@ mod parser {
 
             if let Some(&ch) = first {
                 if ch.is_ascii_digit() {
-                    return self.parse_number::<i32>().map(|ttl| Some(ttl));
+                    return self.parse_ttl().map(Some);
                 }
             }
 
             Ok(None)
         }
 
+        /// A TTL is an unsigned 31 bit number of seconds (RFC 2181 section 8)
+        fn parse_ttl(&mut self) -> Result<i32, RDNSError> {
+            self.parse_time_interval().and_then(|ttl| {
+                i32::try_from(ttl).map_err(|_| {
+                    RDNSError::MasterFileFormatError(
+                        format!("TTL {} is greater than the maximum of {}", ttl, i32::MAX),
+                        self.state.current_position(),
+                    )
+                })
+            })
+        }
+
+        /// The SOA refresh, retry and expire timers are signed 32 bit numbers of seconds
+        fn parse_soa_timer(&mut self, field: &str) -> Result<i32, RDNSError> {
+            self.parse_time_interval().and_then(|time| {
+                i32::try_from(time).map_err(|_| {
+                    RDNSError::MasterFileFormatError(
+                        format!(
+                            "SOA {} {} is greater than the maximum of {}",
+                            field,
+                            time,
+                            i32::MAX
+                        ),
+                        self.state.current_position(),
+                    )
+                })
+            })
+        }
+
+        /// A number of seconds, optionally written with BIND style units such as `1h30m`
+        fn parse_time_interval(&mut self) -> Result<u32, RDNSError> {
+            let position = self.state.current_position();
+
+            let mut str = String::new();
+            while let Some(&ch) = self.state.peek_char() {
+                if ch.is_ascii_alphanumeric() {
+                    str.push(self.state.next_char().unwrap() as char);
+                } else {
+                    break;
+                }
+            }
+
+            rdns_core::ttl::parse(&str).ok_or_else(|| {
+                RDNSError::MasterFileFormatError(
+                    format!("Invalid time interval [{}]", str),
+                    position,
+                )
+            })
+        }
+
         fn parse_ip_addr(&mut self) -> Result<Ipv4Addr, RDNSError> {
             let mut addr: u32 = 0;
 
@@ -416,11 +1150,10 @@ mod parser {
                     }
                     Some(b'.' | b' ' | b'\t') | None => {
                         self.state.next_char();
-                        println!("parse {}", part);
                         let parsed = part.parse::<u8>();
                         match parsed {
                             Ok(v) => {
-                                addr |= (v as u32) << 8 * (3 - part_number);
+                                addr |= (v as u32) << (8 * (3 - part_number));
                                 part_number += 1;
                                 part = String::new();
                             }
@@ -448,9 +1181,134 @@ mod parser {
             Ok(Ipv4Addr::from(addr))
         }
 
-        fn parse_common_in_rr(&mut self) -> Result<(), RDNSError> {
-            loop {
-                self.chomp();
+        /// Whether the RDATA is in the RFC 3597 generic format, `\\# <length> <hex>`
+        fn is_generic_rdata(&mut self) -> bool {
+            let mut ahead = self.state.borrow().clone();
+            matches!(
+                (ahead.next(), ahead.next(), ahead.next()),
+                (Some(b'\\'), Some(b'#'), Some(b' ' | b'\t') | None)
+            )
+        }
+
+        fn parse_generic_rdata(
+            &mut self,
+            rr_type: &rdns_core::RRType<u16>,
+        ) -> Result<rdns_core::record::RData, RDNSError> {
+            self.state.next_char();
+            self.state.next_char();
+            self.chomp();
+
+            let rd_length = self.parse_number::<u16>()?;
+
+            let mut hex = String::new();
+            while self.more_rdata()? {
+                hex.push_str(&self.get_token());
+            }
+
+            let data = decode_hex(&hex).ok_or_else(|| {
+                RDNSError::MasterFileFormatError(
+                    "Invalid hexadecimal data".to_string(),
+                    self.state.current_position(),
+                )
+            })?;
+
+            if data.len() != rd_length as usize {
+                return Err(RDNSError::MasterFileFormatError(
+                    format!(
+                        "generic data should be {} bytes but {} were given",
+                        rd_length,
+                        data.len()
+                    ),
+                    self.state.current_position(),
+                ));
+            }
+
+            // Known types are decoded so they are the same as if they had been written normally
+            rdns_core::record::read_resource_data(rr_type, &mut WireReader::new(&data), rd_length)
+                .map_err(|e| {
+                    RDNSError::MasterFileFormatError(e.to_string(), self.state.current_position())
+                })
+        }
+
+        fn parse_ipv6_addr(&mut self) -> Result<Ipv6Addr, RDNSError> {
+            let mut addr = String::new();
+            while let Some(&ch) = self.state.peek_char() {
+                if ch.is_ascii_hexdigit() || ch == b':' || ch == b'.' {
+                    addr.push(self.state.next_char().unwrap() as char);
+                } else {
+                    break;
+                }
+            }
+
+            addr.parse::<Ipv6Addr>().map_err(|_| {
+                RDNSError::MasterFileFormatError(
+                    "Invalid IPv6 address format".to_string(),
+                    self.state.current_position(),
+                )
+            })
+        }
+
+        fn parse_text(&mut self) -> Result<rdns_core::record::TextResourceData, RDNSError> {
+            let mut strings = Vec::new();
+            while self.more_rdata()? {
+                let value = self.parse_character_string()?;
+                strings.extend(rdns_core::record::split_character_string(&value));
+            }
+
+            if strings.is_empty() {
+                return Err(RDNSError::MasterFileFormatError(
+                    "TXT record must contain at least one string".to_string(),
+                    self.state.current_position(),
+                ));
+            }
+
+            Ok(rdns_core::record::TextResourceData(strings))
+        }
+
+        fn parse_wks(
+            &mut self,
+        ) -> Result<rdns_core::record::WellKnownServiceResourceData, RDNSError> {
+            let address = self.parse_ip_addr()?;
+            self.chomp();
+
+            let protocol_text = self.get_token();
+            let protocol = match protocol_text.to_ascii_uppercase().as_str() {
+                "TCP" => 6,
+                "UDP" => 17,
+                _ => protocol_text.parse::<u8>().map_err(|_| {
+                    RDNSError::MasterFileFormatError(
+                        format!("unknown protocol '{}'", protocol_text),
+                        self.state.current_position(),
+                    )
+                })?,
+            };
+
+            let mut ports = Vec::new();
+            while self.more_rdata()? {
+                let service = self.get_token();
+                let port = match well_known_port(&service.to_ascii_lowercase()) {
+                    Some(port) => port,
+                    None => service.parse::<u16>().map_err(|_| {
+                        RDNSError::MasterFileFormatError(
+                            format!("unknown service '{}'", service),
+                            self.state.current_position(),
+                        )
+                    })?,
+                };
+                ports.push(port);
+            }
+
+            Ok(rdns_core::record::WellKnownServiceResourceData::from_ports(
+                address, protocol, &ports,
+            ))
+        }
+
+        /// Moves to the start of the next item of RDATA for types which take a variable number of
+        /// items, following a multi-line block onto later lines. Returns false once the end of
+        /// the resource record has been reached.
+        fn more_rdata(&mut self) -> Result<bool, RDNSError> {
+            loop {
+                self.chomp();
                 match self.state.peek_char() {
                     Some(b'(') => {
                         if self.multiline {
@@ -473,13 +1331,141 @@ mod parser {
 
                         self.multiline = false;
                         self.state.next_char();
-                        break;
                     }
                     Some(b';') | None => {
-                        self.state.next_line();
-                        self.chomp();
+                        if !self.multiline {
+                            return Ok(false);
+                        }
+
+                        if !self.state.next_line()? {
+                            return Err(RDNSError::MasterFileFormatError(
+                                "Unterminated multi-line block".to_string(),
+                                self.state.current_position(),
+                            ));
+                        }
+                    }
+                    Some(_) => return Ok(true),
+                }
+            }
+        }
+
+        /// Parses a `<character-string>`, either quoted or a contiguous run of characters, with
+        /// `\X` and `\DDD` escapes
+        fn parse_character_string(&mut self) -> Result<Vec<u8>, RDNSError> {
+            let quoted = self.state.peek_char() == Some(&b'"');
+            if quoted {
+                self.state.next_char();
+            }
+
+            let mut value = Vec::new();
+            loop {
+                match self.state.peek_char() {
+                    Some(b'"') if quoted => {
+                        self.state.next_char();
+                        break;
+                    }
+                    Some(b' ' | b'\t' | b';' | b'(' | b')') if !quoted => break,
+                    Some(b'\\') => {
+                        self.state.next_char();
+                        value.push(self.parse_escape()?);
+                    }
+                    Some(_) => value.push(self.state.next_char().unwrap()),
+                    None if quoted => {
+                        return Err(RDNSError::MasterFileFormatError(
+                            "Unterminated quoted string".to_string(),
+                            self.state.current_position(),
+                        ));
+                    }
+                    None => break,
+                }
+            }
+
+            if !quoted && value.is_empty() {
+                return Err(RDNSError::MasterFileFormatError(
+                    "Expected a character-string".to_string(),
+                    self.state.current_position(),
+                ));
+            }
+
+            Ok(value)
+        }
+
+        /// Parses a `<character-string>` which must fit in a single string on the wire
+        fn parse_single_character_string(&mut self) -> Result<Vec<u8>, RDNSError> {
+            let value = self.parse_character_string()?;
+            if value.len() > 255 {
+                return Err(RDNSError::MasterFileFormatError(
+                    "character-string is longer than 255 bytes".to_string(),
+                    self.state.current_position(),
+                ));
+            }
+
+            Ok(value)
+        }
+
+        /// Parses the character following a backslash, which is either three decimal digits
+        /// giving the value of an octet or a character to be taken literally
+        fn parse_escape(&mut self) -> Result<u8, RDNSError> {
+            match self.state.peek_char() {
+                Some(b'0'..=b'9') => {
+                    let mut digits = String::new();
+                    while digits.len() < 3 {
+                        match self.state.peek_char() {
+                            Some(b'0'..=b'9') => {
+                                digits.push(self.state.next_char().unwrap() as char)
+                            }
+                            _ => break,
+                        }
+                    }
+
+                    match digits.parse::<u8>() {
+                        Ok(v) if digits.len() == 3 => Ok(v),
+                        _ => Err(RDNSError::MasterFileFormatError(
+                            "Invalid \\DDD escape sequence".to_string(),
+                            self.state.current_position(),
+                        )),
+                    }
+                }
+                Some(_) => Ok(self.state.next_char().unwrap()),
+                None => Err(RDNSError::MasterFileFormatError(
+                    "Incomplete escape sequence".to_string(),
+                    self.state.current_position(),
+                )),
+            }
+        }
+
+        fn parse_common_in_rr(&mut self) -> Result<(), RDNSError> {
+            loop {
+                self.chomp();
+                match self.state.peek_char() {
+                    Some(b'(') => {
+                        if self.multiline {
+                            return Err(RDNSError::MasterFileFormatError(
+                                "Cannot nest multi-line blocks".to_string(),
+                                self.state.current_position(),
+                            ));
+                        }
+
+                        self.multiline = true;
+                        self.state.next_char();
+                    }
+                    Some(b')') => {
+                        if !self.multiline {
+                            return Err(RDNSError::MasterFileFormatError(
+                                "Not in a multi-line block".to_string(),
+                                self.state.current_position(),
+                            ));
+                        }
+
+                        self.multiline = false;
+                        self.state.next_char();
                         break;
                     }
+                    Some(b';') | None => {
+                        if !self.multiline || !self.state.next_line()? {
+                            break;
+                        }
+                    }
                     Some(_) => break,
                 }
             }
@@ -505,17 +1491,17 @@ mod parser {
             })
         }
 
-        fn get_text(&mut self) -> Result<String, RDNSError> {
+        /// Takes characters up to the next whitespace, comment or parenthesis
+        fn get_token(&mut self) -> String {
             let mut str = String::new();
             while let Some(&ch) = self.state.peek_char() {
-                if self.is_character(ch) {
-                    str.push(self.state.next_char().unwrap() as char);
-                } else {
+                if self.is_whitespace(ch) || matches!(ch, b';' | b'(' | b')') {
                     break;
                 }
+                str.push(self.state.next_char().unwrap() as char);
             }
 
-            Ok(str)
+            str
         }
 
         fn chomp(&mut self) -> bool {
@@ -529,27 +1515,66 @@ mod parser {
         }
 
         fn is_character(&self, ch: u8) -> bool {
-            match ch {
-                b'a'..=b'z' | b'A'..=b'Z' => true,
-                _ => false,
-            }
+            ch.is_ascii_alphabetic()
         }
 
         fn is_whitespace(&self, ch: u8) -> bool {
-            match ch {
-                b' ' | b'\t' => true,
-                _ => false,
-            }
+            matches!(ch, b' ' | b'\t')
         }
     }
+
+    fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+        if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+            return None;
+        }
+
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+            .collect()
+    }
+
+    /// Port numbers for service mnemonics which may be used in WKS records
+    fn well_known_port(service: &str) -> Option<u16> {
+        Some(match service {
+            "echo" => 7,
+            "discard" => 9,
+            "daytime" => 13,
+            "ftp-data" => 20,
+            "ftp" => 21,
+            "ssh" => 22,
+            "telnet" => 23,
+            "smtp" => 25,
+            "time" => 37,
+            "whois" => 43,
+            "domain" => 53,
+            "tftp" => 69,
+            "finger" => 79,
+            "http" => 80,
+            "pop3" => 110,
+            "sunrpc" => 111,
+            "nntp" => 119,
+            "ntp" => 123,
+            "imap" => 143,
+            "snmp" => 161,
+            "ldap" => 389,
+            "https" => 443,
+            _ => return None,
+        })
+    }
 }
 
 #[cfg(test)]
+#[allow(clippy::get_first)]
 mod tests {
-    use crate::txt_config::parser;
-    use rdns_core::record::ResourceData;
+    use crate::diagnostic::{Diagnostic, IncludeSite, Severity};
+    use crate::txt_config::{load_txt_config, load_txt_zone, parser, TxtZoneParser};
+    use rdns_core::error::{LineCharPos, RDNSError};
+    use rdns_core::record::{RData, ResourceData};
     use std::collections::HashSet;
     use std::io::{BufRead, Cursor, Lines};
+    use std::net::{Ipv4Addr, Ipv6Addr};
+    use std::path::PathBuf;
 
     #[test]
     fn parse_comment_on_own_line() {
@@ -602,12 +1627,12 @@ mod tests {
             "exemplar.com.",
             <rdns_core::name::Name as Into<String>>::into(first_record.name.clone())
         );
-        assert_eq!(rdns_core::RRType::CNAME, first_record.rr_type);
+        assert_eq!(rdns_core::RRType::CNAME, first_record.rr_type());
         assert_eq!(rdns_core::RRClass::IN, first_record.class);
         assert_eq!(300, first_record.ttl);
         assert_eq!(
             rdns_core::name::Name::parse(
-                &mut "example.com"
+                &mut "example.com."
                     .to_string()
                     .into_bytes()
                     .into_iter()
@@ -635,10 +1660,84 @@ mod tests {
             "exemplar.com.",
             <rdns_core::name::Name as Into<String>>::into(first_record.name.clone())
         );
-        assert_eq!(rdns_core::RRType::A, first_record.rr_type);
+        assert_eq!(rdns_core::RRType::A, first_record.rr_type());
+        assert_eq!(rdns_core::RRClass::IN, first_record.class);
+        assert_eq!(300, first_record.ttl);
+        assert_eq!(
+            rdns_core::record::RData::A(rdns_core::record::AliasResourceData(Ipv4Addr::new(
+                1, 2, 3, 4
+            ))),
+            first_record.rdata
+        );
+    }
+
+    #[test]
+    fn parse_ipv6_address_rr() {
+        let records = parser::TxtConfigParser::parse(
+            &mut as_lines("exemplar.com. IN 300 AAAA 2001:db8::1".to_string()),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        assert_eq!(1, records.len());
+
+        let first_record = records.first().unwrap().clone();
+        assert_eq!(
+            "exemplar.com.",
+            <rdns_core::name::Name as Into<String>>::into(first_record.name.clone())
+        );
+        assert_eq!(rdns_core::RRType::AAAA, first_record.rr_type());
         assert_eq!(rdns_core::RRClass::IN, first_record.class);
         assert_eq!(300, first_record.ttl);
-        assert_eq!(vec![1, 2, 3, 4], first_record.rdata.serialise());
+        assert_eq!(
+            vec![0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
+            first_record.rdata.serialise()
+        );
+    }
+
+    #[test]
+    fn parse_ipv6_address_textual_forms() {
+        let forms = [
+            (
+                "2001:0db8:0000:0000:0000:ff00:0042:8329",
+                "2001:db8::ff00:42:8329",
+            ),
+            ("2001:DB8::FF00:42:8329", "2001:db8::ff00:42:8329"),
+            ("::1", "::1"),
+            ("::", "::"),
+            ("fe80::", "fe80::"),
+            ("::ffff:192.0.2.1", "::ffff:192.0.2.1"),
+            ("64:ff9b::192.0.2.33", "64:ff9b::c000:221"),
+        ];
+
+        for (input, expected) in forms {
+            let records = parser::TxtConfigParser::parse(
+                &mut as_lines(format!("exemplar.com. IN AAAA {} ; comment", input)),
+                rdns_core::name::Name::root(),
+            )
+            .unwrap();
+
+            let expected = expected.parse::<Ipv6Addr>().unwrap();
+            assert_eq!(
+                expected.octets().to_vec(),
+                records.first().unwrap().rdata.serialise()
+            );
+        }
+    }
+
+    #[test]
+    fn parse_invalid_ipv6_address() {
+        for input in ["2001:db8::1::2", "2001:db8:1", "12345::", "::ffff:192.0.2"] {
+            let result = parser::TxtConfigParser::parse(
+                &mut as_lines(format!("exemplar.com. IN AAAA {}", input)),
+                rdns_core::name::Name::root(),
+            );
+
+            assert!(matches!(
+                result,
+                Err(RDNSError::MasterFileFormatError(_, _))
+            ));
+        }
     }
 
     #[test]
@@ -656,12 +1755,12 @@ mod tests {
             "exemplar.com.",
             <rdns_core::name::Name as Into<String>>::into(first_record.name.clone())
         );
-        assert_eq!(rdns_core::RRType::NS, first_record.rr_type);
+        assert_eq!(rdns_core::RRType::NS, first_record.rr_type());
         assert_eq!(rdns_core::RRClass::IN, first_record.class);
         assert_eq!(0, first_record.ttl);
         assert_eq!(
             rdns_core::name::Name::parse(
-                &mut "hosting".to_string().into_bytes().into_iter().peekable(),
+                &mut "hosting.".to_string().into_bytes().into_iter().peekable(),
                 HashSet::new(),
             )
             .unwrap()
@@ -671,107 +1770,537 @@ mod tests {
     }
 
     #[test]
-    fn use_at_symbol_in_place_of_owner_name() {
+    fn parse_service_rr() {
         let records = parser::TxtConfigParser::parse(
-            &mut as_lines("$ORIGIN exemplar.com.\n@ IN 300 CNAME example.com".to_string()),
+            &mut as_lines("_sip._tcp.example.com. IN 300 SRV 10 60 5060 sip".to_string()),
             rdns_core::name::Name::root(),
         )
         .unwrap();
 
         assert_eq!(1, records.len());
 
-        let first_record = records.get(0).unwrap().clone();
+        let first_record = records.first().unwrap().clone();
         assert_eq!(
-            "exemplar.com.",
+            "_sip._tcp.example.com.",
             <rdns_core::name::Name as Into<String>>::into(first_record.name.clone())
         );
-        assert_eq!(rdns_core::RRType::CNAME, first_record.rr_type);
+        assert_eq!(rdns_core::RRType::SRV, first_record.rr_type());
         assert_eq!(rdns_core::RRClass::IN, first_record.class);
         assert_eq!(300, first_record.ttl);
-        assert_eq!(
+
+        let mut expected = vec![0, 10, 0, 60, 0x13, 0xC4];
+        expected.extend(
             rdns_core::name::Name::parse(
-                &mut "example.com"
-                    .to_string()
-                    .into_bytes()
-                    .into_iter()
-                    .peekable(),
+                &mut "sip.".to_string().into_bytes().into_iter().peekable(),
                 HashSet::new(),
             )
             .unwrap()
             .raw(),
-            first_record.rdata.serialise()
         );
+        assert_eq!(expected, first_record.rdata.serialise());
     }
 
     #[test]
-    fn parse_soa_rr_on_single_line() {
+    fn parse_service_rr_with_port_out_of_range() {
+        let result = parser::TxtConfigParser::parse(
+            &mut as_lines("_ldap._tcp.example.com. IN SRV 0 0 65536 ldap".to_string()),
+            rdns_core::name::Name::root(),
+        );
+
+        assert!(matches!(
+            result,
+            Err(RDNSError::MasterFileFormatError(_, _))
+        ));
+    }
+
+    #[test]
+    fn parse_text_rr() {
         let records = parser::TxtConfigParser::parse(
-            &mut as_lines("@ IN SOA nameserver1 owner 20 7200 600 3600000 60".to_string()),
+            &mut as_lines(
+                "example.com. IN 300 TXT \"v=spf1 -all\" unquoted \"\" ; comment".to_string(),
+            ),
             rdns_core::name::Name::root(),
         )
         .unwrap();
 
         assert_eq!(1, records.len());
 
-        let first_record = records.get(0).unwrap().clone();
-        assert_eq!(
-            ".",
-            <rdns_core::name::Name as Into<String>>::into(first_record.name.clone())
-        );
-        assert_eq!(rdns_core::RRType::SOA, first_record.rr_type);
+        let first_record = records.first().unwrap().clone();
+        assert_eq!(rdns_core::RRType::TXT, first_record.rr_type());
         assert_eq!(rdns_core::RRClass::IN, first_record.class);
-        assert_eq!(0, first_record.ttl);
-        assert_eq!(
-            rdns_core::record::SOAResourceData {
-                primary_name: rdns_core::name::Name::parse(
-                    &mut "nameserver1"
-                        .to_string()
-                        .into_bytes()
-                        .into_iter()
-                        .peekable(),
-                    HashSet::new(),
-                )
-                .unwrap(),
-                responsible_name: rdns_core::name::Name::parse(
-                    &mut "owner".to_string().into_bytes().into_iter().peekable(),
-                    HashSet::new(),
-                )
-                .unwrap(),
-                serial: 20,
-                refresh: 7200,
-                retry: 600,
-                expire: 3600000,
-                minimum: 60,
-            }
-            .serialise(),
-            first_record.rdata.serialise()
-        );
+        assert_eq!(300, first_record.ttl);
+
+        let mut expected = vec![11];
+        expected.extend_from_slice(b"v=spf1 -all");
+        expected.push(8);
+        expected.extend_from_slice(b"unquoted");
+        expected.push(0);
+        assert_eq!(expected, first_record.rdata.serialise());
     }
 
     #[test]
-    fn parse_soa_rr_on_multiple_lines() {
+    fn parse_text_rr_with_escapes() {
         let records = parser::TxtConfigParser::parse(
             &mut as_lines(
-                "@ IN SOA nameserver1 owner (20\n 7200\n 600\n 3600000\n 60)".to_string(),
+                r#"example.com. IN TXT "say \"hi\"; \\ \065\010" semi\;colon"#.to_string(),
             ),
             rdns_core::name::Name::root(),
         )
         .unwrap();
 
-        assert_eq!(1, records.len());
+        let mut expected = vec![14];
+        expected.extend_from_slice(b"say \"hi\"; \\ A\n");
+        expected.push(10);
+        expected.extend_from_slice(b"semi;colon");
+        assert_eq!(expected, records.first().unwrap().rdata.serialise());
+    }
 
-        let first_record = records.get(0).unwrap().clone();
+    #[test]
+    fn parse_text_rr_split_over_multiple_lines() {
+        let records = parser::TxtConfigParser::parse(
+            &mut as_lines(
+                "example.com. IN TXT ( \"v=DKIM1; k=rsa; \" ; key\n   \"p=MIGfMA0\" )\nwww IN A 1.2.3.4"
+                    .to_string(),
+            ),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        assert_eq!(2, records.len());
+
+        let mut expected = vec![16];
+        expected.extend_from_slice(b"v=DKIM1; k=rsa; ");
+        expected.push(9);
+        expected.extend_from_slice(b"p=MIGfMA0");
+        assert_eq!(expected, records.first().unwrap().rdata.serialise());
+    }
+
+    #[test]
+    fn parse_text_rr_after_multi_line_soa() {
+        let zone = TxtZoneParser::new()
+            .with_origin(test_name("example.com."))
+            .parse_str(
+                "@ 3600 IN SOA ns1 admin (\n    1 ; serial\n    7200\n    900\n    1209600\n    60 )\n  3600 IN NS ns1\n@ 3600 IN TXT \"v=spf1 -all\"\nns1 3600 IN A 10.0.0.53\nwww 3600 IN A 10.0.0.80",
+            )
+            .unwrap();
+
+        assert!(zone.diagnostics.is_empty());
+        let records = zone
+            .entries
+            .iter()
+            .map(|entry| entry.record.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "example.com. 3600 IN SOA ns1.example.com. admin.example.com. 1 7200 900 1209600 60",
+                "example.com. 3600 IN NS ns1.example.com.",
+                "example.com. 3600 IN TXT \"v=spf1 -all\"",
+                "ns1.example.com. 3600 IN A 10.0.0.53",
+                "www.example.com. 3600 IN A 10.0.0.80",
+            ],
+            records
+        );
+    }
+
+    #[test]
+    fn unexpected_data_after_record() {
+        let result = parser::TxtConfigParser::parse(
+            &mut as_lines("example.com. 60 IN A 10.0.0.1 10.0.0.2".to_string()),
+            rdns_core::name::Name::root(),
+        );
+
+        assert!(matches!(
+            result,
+            Err(RDNSError::MasterFileFormatError(_, _))
+        ));
+    }
+
+    #[test]
+    fn parse_text_rr_longer_than_a_character_string() {
+        let value = "a".repeat(300);
+        let records = parser::TxtConfigParser::parse(
+            &mut as_lines(format!("example.com. IN TXT \"{}\"", value)),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        let serialised = records.first().unwrap().rdata.serialise();
+        assert_eq!(302, serialised.len());
+        assert_eq!(255, serialised[0]);
+        assert_eq!(45, serialised[256]);
+    }
+
+    #[test]
+    fn parse_text_rr_with_unterminated_string() {
+        let result = parser::TxtConfigParser::parse(
+            &mut as_lines("example.com. IN TXT \"no end".to_string()),
+            rdns_core::name::Name::root(),
+        );
+
+        assert!(matches!(
+            result,
+            Err(RDNSError::MasterFileFormatError(_, _))
+        ));
+    }
+
+    #[test]
+    fn parse_text_rr_without_strings() {
+        let result = parser::TxtConfigParser::parse(
+            &mut as_lines("example.com. IN TXT ; nothing".to_string()),
+            rdns_core::name::Name::root(),
+        );
+
+        assert!(matches!(
+            result,
+            Err(RDNSError::MasterFileFormatError(_, _))
+        ));
+    }
+
+    #[test]
+    fn parse_host_info_rr() {
+        let records = parser::TxtConfigParser::parse(
+            &mut as_lines("host.example.com. IN HINFO \"INTEL-386\" Linux".to_string()),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        let first_record = records.first().unwrap().clone();
+        assert_eq!(rdns_core::RRType::HINFO, first_record.rr_type());
+
+        let mut expected = vec![9];
+        expected.extend_from_slice(b"INTEL-386");
+        expected.push(5);
+        expected.extend_from_slice(b"Linux");
+        assert_eq!(expected, first_record.rdata.serialise());
+    }
+
+    #[test]
+    fn parse_host_info_rr_missing_os() {
+        let result = parser::TxtConfigParser::parse(
+            &mut as_lines("host.example.com. IN HINFO \"INTEL-386\"".to_string()),
+            rdns_core::name::Name::root(),
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn indented_first_record_has_no_owner() {
+        for zone in [
+            " IN A 1.2.3.4\n",
+            "$ORIGIN example.com.\n\t300 IN A 1.2.3.4\n",
+        ] {
+            let zone = TxtZoneParser::new().parse_str(zone).unwrap();
+
+            assert!(zone.entries.is_empty());
+            assert_eq!(1, zone.diagnostics.len());
+            assert_eq!(
+                "record has no owner name and there is no previous owner",
+                zone.diagnostics[0].message
+            );
+        }
+    }
+
+    #[test]
+    fn parse_host_info_rr_longer_than_a_character_string() {
+        let result = parser::TxtConfigParser::parse(
+            &mut as_lines(format!(
+                "host.example.com. IN HINFO \"{}\" Linux",
+                "x".repeat(256)
+            )),
+            rdns_core::name::Name::root(),
+        );
+
+        assert!(matches!(
+            result,
+            Err(RDNSError::MasterFileFormatError(message, _)) if message.contains("longer than 255 bytes")
+        ));
+    }
+
+    #[test]
+    fn parse_mail_info_rr() {
+        let records = parser::TxtConfigParser::parse(
+            &mut as_lines("list.example.com. IN MINFO owner errors".to_string()),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        let first_record = records.first().unwrap().clone();
+        assert_eq!(rdns_core::RRType::MINFO, first_record.rr_type());
+
+        let mut expected = test_name("owner.").raw();
+        expected.extend(test_name("errors.").raw());
+        assert_eq!(expected, first_record.rdata.serialise());
+    }
+
+    #[test]
+    fn parse_mailbox_rrs() {
+        for rr_type in ["MD", "MF", "MB", "MG", "MR"] {
+            let records = parser::TxtConfigParser::parse(
+                &mut as_lines(format!("example.com. IN {} mail", rr_type)),
+                rdns_core::name::Name::root(),
+            )
+            .unwrap();
+
+            let first_record = records.first().unwrap().clone();
+            assert_eq!(
+                rdns_core::RRType::try_from(rr_type).unwrap(),
+                first_record.rr_type()
+            );
+            assert_eq!(test_name("mail.").raw(), first_record.rdata.serialise());
+        }
+    }
+
+    #[test]
+    fn parse_well_known_service_rr() {
+        let records = parser::TxtConfigParser::parse(
+            &mut as_lines("host.example.com. IN WKS 10.0.0.1 TCP ( ftp smtp\n 80 )".to_string()),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        let first_record = records.first().unwrap().clone();
+        assert_eq!(rdns_core::RRType::WKS, first_record.rr_type());
+
+        let expected = rdns_core::record::WellKnownServiceResourceData::from_ports(
+            "10.0.0.1".parse().unwrap(),
+            6,
+            &[21, 25, 80],
+        );
+        assert_eq!(expected.serialise(), first_record.rdata.serialise());
+    }
+
+    #[test]
+    fn parse_well_known_service_rr_with_unknown_service() {
+        let result = parser::TxtConfigParser::parse(
+            &mut as_lines("host.example.com. IN WKS 10.0.0.1 17 not-a-service".to_string()),
+            rdns_core::name::Name::root(),
+        );
+
+        assert!(matches!(
+            result,
+            Err(RDNSError::MasterFileFormatError(_, _))
+        ));
+    }
+
+    #[test]
+    fn parse_null_rr_is_not_allowed() {
+        let result = parser::TxtConfigParser::parse(
+            &mut as_lines("example.com. IN NULL".to_string()),
+            rdns_core::name::Name::root(),
+        );
+
+        assert!(matches!(
+            result,
+            Err(RDNSError::MasterFileFormatError(_, _))
+        ));
+    }
+
+    #[test]
+    fn parse_unknown_type_and_class_in_generic_format() {
+        let records = parser::TxtConfigParser::parse(
+            &mut as_lines("example.com. CLASS32 300 TYPE65280 \\# 3 abCD ef".to_string()),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        let first_record = records.first().unwrap().clone();
+        assert_eq!(rdns_core::RRType::UNKNOWN(65280), first_record.rr_type());
+        assert_eq!(rdns_core::RRClass::UNKNOWN(32), first_record.class);
+        assert_eq!(300, first_record.ttl);
+        assert_eq!(vec![0xAB, 0xCD, 0xEF], first_record.rdata.serialise());
+    }
+
+    #[test]
+    fn parse_known_type_in_generic_format() {
+        let records = parser::TxtConfigParser::parse(
+            &mut as_lines("example.com. IN TYPE1 \\# 4 0A000001".to_string()),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        let first_record = records.first().unwrap().clone();
+        assert_eq!(rdns_core::RRType::A, first_record.rr_type());
+        assert_eq!(vec![10, 0, 0, 1], first_record.rdata.serialise());
+    }
+
+    #[test]
+    fn parse_generic_format_over_multiple_lines() {
+        let records = parser::TxtConfigParser::parse(
+            &mut as_lines("example.com. IN NULL \\# 4 ( DEAD ; comment\n BEEF )".to_string()),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        let first_record = records.first().unwrap().clone();
+        assert_eq!(rdns_core::RRType::NULL, first_record.rr_type());
+        assert_eq!(vec![0xDE, 0xAD, 0xBE, 0xEF], first_record.rdata.serialise());
+    }
+
+    #[test]
+    fn parse_empty_generic_format() {
+        let records = parser::TxtConfigParser::parse(
+            &mut as_lines("example.com. IN TYPE65280 \\# 0".to_string()),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        assert!(records.first().unwrap().rdata.serialise().is_empty());
+    }
+
+    #[test]
+    fn parse_generic_format_with_wrong_length() {
+        for input in [
+            "example.com. IN TYPE65280 \\# 2 ABCDEF",
+            "example.com. IN TYPE65280 \\# 2 ABC",
+            "example.com. IN TYPE65280 \\# 2 XYZW",
+            "example.com. IN A \\# 3 0A0000",
+        ] {
+            let result = parser::TxtConfigParser::parse(
+                &mut as_lines(input.to_string()),
+                rdns_core::name::Name::root(),
+            );
+
+            assert!(matches!(
+                result,
+                Err(RDNSError::MasterFileFormatError(_, _))
+            ));
+        }
+    }
+
+    #[test]
+    fn parse_unknown_type_requires_generic_format() {
+        let result = parser::TxtConfigParser::parse(
+            &mut as_lines("example.com. IN TYPE65280 abcdef".to_string()),
+            rdns_core::name::Name::root(),
+        );
+
+        assert!(matches!(
+            result,
+            Err(RDNSError::MasterFileFormatError(_, _))
+        ));
+    }
+
+    #[test]
+    fn parse_unrecognised_type_mnemonic() {
+        let result = parser::TxtConfigParser::parse(
+            &mut as_lines("example.com. IN EXAMPLE 1.2.3.4".to_string()),
+            rdns_core::name::Name::root(),
+        );
+
+        assert!(matches!(
+            result,
+            Err(RDNSError::MasterFileFormatError(_, _))
+        ));
+    }
+
+    #[test]
+    fn use_at_symbol_in_place_of_owner_name() {
+        let records = parser::TxtConfigParser::parse(
+            &mut as_lines("$ORIGIN exemplar.com.\n@ IN 300 CNAME example.com".to_string()),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        assert_eq!(1, records.len());
+
+        let first_record = records.get(0).unwrap().clone();
+        assert_eq!(
+            "exemplar.com.",
+            <rdns_core::name::Name as Into<String>>::into(first_record.name.clone())
+        );
+        assert_eq!(rdns_core::RRType::CNAME, first_record.rr_type());
+        assert_eq!(rdns_core::RRClass::IN, first_record.class);
+        assert_eq!(300, first_record.ttl);
+        assert_eq!(
+            rdns_core::name::Name::parse(
+                &mut "example.com.exemplar.com."
+                    .to_string()
+                    .into_bytes()
+                    .into_iter()
+                    .peekable(),
+                HashSet::new(),
+            )
+            .unwrap()
+            .raw(),
+            first_record.rdata.serialise()
+        );
+    }
+
+    #[test]
+    fn relative_names_are_made_absolute_against_origin() {
+        let records = parser::TxtConfigParser::parse(
+            &mut as_lines(
+                "$ORIGIN example.com.\nwww IN 300 CNAME host\nmail IN MX 10 mail.example.org."
+                    .to_string(),
+            ),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        assert_eq!(2, records.len());
+        assert_eq!(
+            "www.example.com. 300 IN CNAME host.example.com.",
+            records[0].to_string()
+        );
+        assert_eq!(
+            "mail.example.com. 300 IN MX 10 mail.example.org.",
+            records[1].to_string()
+        );
+    }
+
+    #[test]
+    fn relative_origin_is_made_absolute_against_previous_origin() {
+        let records = parser::TxtConfigParser::parse(
+            &mut as_lines("$ORIGIN com.\n$ORIGIN example\n@ IN 300 NS ns1".to_string()),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            "example.com. 300 IN NS ns1.example.com.",
+            records.first().unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn unicode_names_are_converted_to_a_labels() {
+        let records = parser::TxtConfigParser::parse(
+            &mut as_lines("$ORIGIN bücher.example.\nwww IN 300 CNAME ünicode".to_string()),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        let first_record = records.first().unwrap();
+        assert_eq!(
+            "www.xn--bcher-kva.example. 300 IN CNAME xn--nicode-2ya.xn--bcher-kva.example.",
+            first_record.to_string()
+        );
+        assert_eq!("www.bücher.example.", first_record.name.to_unicode());
+    }
+
+    #[test]
+    fn parse_soa_rr_on_single_line() {
+        let records = parser::TxtConfigParser::parse(
+            &mut as_lines("@ IN SOA nameserver1 owner 20 7200 600 3600000 60".to_string()),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        assert_eq!(1, records.len());
+
+        let first_record = records.get(0).unwrap().clone();
         assert_eq!(
             ".",
             <rdns_core::name::Name as Into<String>>::into(first_record.name.clone())
         );
-        assert_eq!(rdns_core::RRType::SOA, first_record.rr_type);
+        assert_eq!(rdns_core::RRType::SOA, first_record.rr_type());
         assert_eq!(rdns_core::RRClass::IN, first_record.class);
-        assert_eq!(0, first_record.ttl);
+        assert_eq!(60, first_record.ttl);
         assert_eq!(
             rdns_core::record::SOAResourceData {
                 primary_name: rdns_core::name::Name::parse(
-                    &mut "nameserver1"
+                    &mut "nameserver1."
                         .to_string()
                         .into_bytes()
                         .into_iter()
@@ -780,7 +2309,7 @@ mod tests {
                 )
                 .unwrap(),
                 responsible_name: rdns_core::name::Name::parse(
-                    &mut "owner".to_string().into_bytes().into_iter().peekable(),
+                    &mut "owner.".to_string().into_bytes().into_iter().peekable(),
                     HashSet::new(),
                 )
                 .unwrap(),
@@ -795,7 +2324,831 @@ mod tests {
         );
     }
 
-    fn as_lines(input: String) -> Lines<Cursor<String>> {
-        Cursor::new(input).lines()
+    #[test]
+    fn parse_soa_rr_on_multiple_lines() {
+        let records = parser::TxtConfigParser::parse(
+            &mut as_lines(
+                "@ IN SOA nameserver1 owner (20\n 7200\n 600\n 3600000\n 60)".to_string(),
+            ),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        assert_eq!(1, records.len());
+
+        let first_record = records.get(0).unwrap().clone();
+        assert_eq!(
+            ".",
+            <rdns_core::name::Name as Into<String>>::into(first_record.name.clone())
+        );
+        assert_eq!(rdns_core::RRType::SOA, first_record.rr_type());
+        assert_eq!(rdns_core::RRClass::IN, first_record.class);
+        assert_eq!(60, first_record.ttl);
+        assert_eq!(
+            rdns_core::record::SOAResourceData {
+                primary_name: rdns_core::name::Name::parse(
+                    &mut "nameserver1."
+                        .to_string()
+                        .into_bytes()
+                        .into_iter()
+                        .peekable(),
+                    HashSet::new(),
+                )
+                .unwrap(),
+                responsible_name: rdns_core::name::Name::parse(
+                    &mut "owner.".to_string().into_bytes().into_iter().peekable(),
+                    HashSet::new(),
+                )
+                .unwrap(),
+                serial: 20,
+                refresh: 7200,
+                retry: 600,
+                expire: 3600000,
+                minimum: 60,
+            }
+            .serialise(),
+            first_record.rdata.serialise()
+        );
+    }
+
+    #[test]
+    fn displayed_records_parse_to_the_same_records() {
+        let input = [
+            "example.com. 300 IN A 10.0.0.1",
+            "example.com. 300 IN AAAA 2001:db8::1",
+            "example.com. 300 IN NS ns1.example.com.",
+            "www.example.com. 300 IN CNAME example.com.",
+            "example.com. 300 IN SOA ns1.example.com. admin.example.com. 1 7200 600 3600000 60",
+            "example.com. 300 IN MX 10 mail.example.com.",
+            "example.com. 300 IN TXT \"v=spf1 -all\" \"say \\\"hi\\\" \\\\\" \"\\000\\200\"",
+            "example.com. 300 IN HINFO \"INTEL-386\" \"Linux\"",
+            "example.com. 300 IN MINFO admin.example.com. errors.example.com.",
+            "example.com. 300 IN WKS 10.0.0.1 6 21 25",
+            "_sip._tcp.example.com. 300 IN SRV 0 5 5060 sip.example.com.",
+            "example.com. 300 IN NULL \\# 2 CAFE",
+            "example.com. 300 CLASS32 TYPE65280 \\# 3 ABCDEF",
+        ];
+
+        for line in input {
+            let records = parser::TxtConfigParser::parse(
+                &mut as_lines(line.to_string()),
+                rdns_core::name::Name::root(),
+            )
+            .unwrap();
+
+            let first_record = records.first().unwrap();
+            assert_eq!(line, first_record.to_string());
+
+            let reparsed = parser::TxtConfigParser::parse(
+                &mut as_lines(first_record.to_string()),
+                rdns_core::name::Name::root(),
+            )
+            .unwrap();
+            assert_eq!(
+                first_record.rdata.serialise(),
+                reparsed.first().unwrap().rdata.serialise()
+            );
+        }
+    }
+
+    #[test]
+    fn record_positions_are_kept() {
+        let entries = parser::TxtConfigParser::parse_zone(
+            &mut as_lines(
+                "$ORIGIN example.com.\n; comment\nwww IN 300 A 10.0.0.1\n    IN 300 A 10.0.0.2"
+                    .to_string(),
+            ),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap()
+        .entries;
+
+        assert_eq!(2, entries.len());
+        assert_eq!(LineCharPos { line: 3, char: 1 }, entries[0].position);
+        assert_eq!(LineCharPos { line: 4, char: 5 }, entries[1].position);
+    }
+
+    #[test]
+    fn ttl_directive_sets_default_ttl() {
+        let zone = parser::TxtConfigParser::parse_zone(
+            &mut as_lines(
+                "$TTL 3600 ; one hour\nexample.com. IN A 10.0.0.1\nexample.com. 60 IN A 10.0.0.2\nexample.com. IN A 10.0.0.3"
+                    .to_string(),
+            ),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        let ttls = zone
+            .entries
+            .iter()
+            .map(|entry| entry.record.ttl)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![3600, 60, 3600], ttls);
+        assert!(zone.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn ttl_directive_can_change() {
+        let records = parser::TxtConfigParser::parse(
+            &mut as_lines(
+                "$TTL 3600\nexample.com. IN A 10.0.0.1\n$TTL 300\nexample.com. IN A 10.0.0.2"
+                    .to_string(),
+            ),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        assert_eq!(3600, records[0].ttl);
+        assert_eq!(300, records[1].ttl);
+    }
+
+    #[test]
+    fn missing_ttl_uses_previous_ttl_with_warning() {
+        let zone = parser::TxtConfigParser::parse_zone(
+            &mut as_lines("example.com. 60 IN A 10.0.0.1\nexample.com. IN A 10.0.0.2".to_string()),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        assert_eq!(60, zone.entries[1].record.ttl);
+        assert_eq!(
+            vec![Diagnostic::warning(
+                "no TTL or $TTL given, using the previous TTL 60 set on line 1".to_string(),
+                Some(LineCharPos { line: 2, char: 1 })
+            )],
+            zone.diagnostics
+        );
+    }
+
+    #[test]
+    fn missing_ttl_uses_soa_minimum_with_warning() {
+        let zone = parser::TxtConfigParser::parse_zone(
+            &mut as_lines(
+                "example.com. IN SOA ns1 admin 1 7200 600 3600000 120\nexample.com. IN A 10.0.0.1"
+                    .to_string(),
+            ),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        assert_eq!(120, zone.entries[0].record.ttl);
+        assert_eq!(120, zone.entries[1].record.ttl);
+        assert_eq!(1, zone.diagnostics.len());
+        assert_eq!(
+            "1:1: warning: no TTL or $TTL given, using the SOA minimum 120 set on line 1",
+            zone.diagnostics[0].to_string()
+        );
+    }
+
+    #[test]
+    fn missing_ttl_is_warned_about_once_for_each_line_setting_it() {
+        let zone = parser::TxtConfigParser::parse_zone(
+            &mut as_lines(
+                "a.example.com. 60 IN A 10.0.0.1\nb.example.com. IN A 10.0.0.2\nc.example.com. IN A 10.0.0.3\nd.example.com. 120 IN A 10.0.0.4\ne.example.com. IN A 10.0.0.5\nf.example.com. IN A 10.0.0.6"
+                    .to_string(),
+            ),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                Diagnostic::warning(
+                    "no TTL or $TTL given, using the previous TTL 60 set on line 1".to_string(),
+                    Some(LineCharPos { line: 2, char: 1 })
+                ),
+                Diagnostic::warning(
+                    "no TTL or $TTL given, using the previous TTL 120 set on line 4".to_string(),
+                    Some(LineCharPos { line: 5, char: 1 })
+                ),
+            ],
+            zone.diagnostics
+        );
+    }
+
+    #[test]
+    fn missing_ttl_without_fall_back_is_zero_with_warning() {
+        let zone = parser::TxtConfigParser::parse_zone(
+            &mut as_lines("example.com. IN A 10.0.0.1".to_string()),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        assert_eq!(0, zone.entries[0].record.ttl);
+        assert_eq!(
+            "1:1: warning: no TTL or $TTL given, using 0 so the record will not be cached",
+            zone.diagnostics[0].to_string()
+        );
+    }
+
+    #[test]
+    fn ttl_out_of_range() {
+        let result = parser::TxtConfigParser::parse(
+            &mut as_lines("$TTL 2147483648\nexample.com. IN A 10.0.0.1".to_string()),
+            rdns_core::name::Name::root(),
+        );
+
+        assert!(matches!(
+            result,
+            Err(RDNSError::MasterFileFormatError(_, _))
+        ));
+    }
+
+    #[test]
+    fn ttls_with_units() {
+        let records = parser::TxtConfigParser::parse(
+            &mut as_lines(
+                "$TTL 1H\nexample.com. IN A 10.0.0.1\nexample.com. 1h30m IN A 10.0.0.2\nexample.com. IN 2d A 10.0.0.3"
+                    .to_string(),
+            ),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        let ttls = records.iter().map(|record| record.ttl).collect::<Vec<_>>();
+        assert_eq!(vec![3600, 5400, 172800], ttls);
+    }
+
+    #[test]
+    fn soa_timers_with_units() {
+        let records = parser::TxtConfigParser::parse(
+            &mut as_lines("example.com. 1d IN SOA ns1 admin (2024010101 2h 15M 2w 1h)".to_string()),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        assert_eq!(86400, records[0].ttl);
+        match &records[0].rdata {
+            RData::SOA(soa) => {
+                assert_eq!(2024010101, soa.serial);
+                assert_eq!(7200, soa.refresh);
+                assert_eq!(900, soa.retry);
+                assert_eq!(1209600, soa.expire);
+                assert_eq!(3600, soa.minimum);
+            }
+            rdata => panic!("expected an SOA record but got {:?}", rdata),
+        }
+    }
+
+    #[test]
+    fn invalid_ttl_unit() {
+        let result = parser::TxtConfigParser::parse(
+            &mut as_lines("example.com. 1x IN A 10.0.0.1".to_string()),
+            rdns_core::name::Name::root(),
+        );
+
+        assert!(matches!(
+            result,
+            Err(RDNSError::MasterFileFormatError(_, _))
+        ));
+    }
+
+    #[test]
+    fn include_merges_records_in_order() {
+        let zone = load_txt_zone(include_test_file("main.zone")).unwrap();
+
+        let records = zone
+            .entries
+            .iter()
+            .map(|entry| {
+                format!(
+                    "{} {} {}",
+                    entry.record.name, entry.record.ttl, entry.record.rdata
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ". 3600 ns1. admin. 1 7200 900 1209600 3600",
+                ". 3600 ns1.",
+                "www.hosts.example.com. 3600 10.0.1.1",
+                "mail.hosts.example.com. 300 10.0.1.2",
+                "ftp.other.example.com. 3600 10.0.1.3",
+                "ns1. 3600 10.0.0.53",
+            ],
+            records
+        );
+        assert!(zone.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn include_records_the_file_of_each_record() {
+        let zone = load_txt_zone(include_test_file("main.zone")).unwrap();
+
+        assert_eq!(Some(include_test_file("main.zone")), zone.entries[0].file);
+        assert_eq!(
+            Some(include_test_file("sub/hosts.zone")),
+            zone.entries[2].file
+        );
+        assert_eq!(LineCharPos { line: 2, char: 1 }, zone.entries[3].position);
+        assert_eq!(Some(include_test_file("main.zone")), zone.entries[5].file);
+    }
+
+    #[test]
+    fn include_of_itself_is_a_cycle() {
+        let zone = load_txt_zone(include_test_file("self.zone")).unwrap();
+
+        assert_eq!(1, zone.entries.len());
+        assert_eq!(1, zone.diagnostics.len());
+        let diagnostic = &zone.diagnostics[0];
+        assert_eq!(Severity::Error, diagnostic.severity);
+        assert!(diagnostic.message.contains("$INCLUDE creates a cycle"));
+        assert_eq!(Some(LineCharPos { line: 2, char: 1 }), diagnostic.position);
+    }
+
+    #[test]
+    fn include_cycle_reports_the_chain() {
+        let zone = load_txt_zone(include_test_file("a.zone")).unwrap();
+
+        assert_eq!(2, zone.entries.len());
+        assert_eq!(1, zone.diagnostics.len());
+        let diagnostic = &zone.diagnostics[0];
+        assert_eq!(Some(include_test_file("b.zone")), diagnostic.file);
+        assert_eq!(Some(LineCharPos { line: 2, char: 1 }), diagnostic.position);
+        assert!(diagnostic.message.contains("b.zone -> "));
+        assert!(diagnostic.message.ends_with("a.zone"));
+    }
+
+    #[test]
+    fn include_error_reports_the_included_file() {
+        let zone = load_txt_zone(include_test_file("broken-parent.zone")).unwrap();
+
+        assert_eq!(2, zone.entries.len());
+        assert_eq!(
+            vec![Diagnostic::error(
+                "Invalid part of IP address".to_string(),
+                Some(LineCharPos { line: 2, char: 21 })
+            )
+            .in_file(Some(include_test_file("sub/broken.zone")))
+            .with_source_line("example.com. 60 IN A 10.0.0.256".to_string())
+            .included_from(
+                Some(include_test_file("broken-parent.zone")),
+                LineCharPos { line: 2, char: 1 }
+            )],
+            zone.diagnostics
+        );
+    }
+
+    #[test]
+    fn nested_include_error_reports_the_chain() {
+        let zone = load_txt_zone(include_test_file("nested-parent.zone")).unwrap();
+
+        assert_eq!(3, zone.entries.len());
+        assert_eq!(1, zone.diagnostics.len());
+        let diagnostic = &zone.diagnostics[0];
+        assert_eq!(Some(include_test_file("sub/broken.zone")), diagnostic.file);
+        assert_eq!(
+            vec![
+                IncludeSite {
+                    file: Some(include_test_file("sub/nested.zone")),
+                    position: LineCharPos { line: 2, char: 1 },
+                },
+                IncludeSite {
+                    file: Some(include_test_file("nested-parent.zone")),
+                    position: LineCharPos { line: 2, char: 1 },
+                },
+            ],
+            diagnostic.included_from
+        );
+        assert!(diagnostic.to_string().ends_with(&format!(
+            "\n  included from {}:2:1\n  included from {}:2:1",
+            include_test_file("sub/nested.zone").display(),
+            include_test_file("nested-parent.zone").display()
+        )));
+    }
+
+    #[test]
+    fn class_set_by_an_included_file_applies_to_the_parent() {
+        let zone = load_txt_zone(include_test_file("class-parent.zone")).unwrap();
+
+        assert_eq!(1, zone.entries.len());
+        assert_eq!(1, zone.diagnostics.len());
+        assert_eq!(
+            "File must only contain one class",
+            zone.diagnostics[0].message
+        );
+        assert_eq!(
+            Some(include_test_file("class-parent.zone")),
+            zone.diagnostics[0].file
+        );
+    }
+
+    #[test]
+    fn include_of_missing_file() {
+        let zone = load_txt_zone(include_test_file("missing-parent.zone")).unwrap();
+
+        assert_eq!(1, zone.diagnostics.len());
+        assert!(zone.diagnostics[0].message.starts_with(&format!(
+            "could not include {} - i/o error: ",
+            include_test_file("missing.zone").display()
+        )));
+        assert!(matches!(
+            load_txt_config(include_test_file("missing-parent.zone")),
+            Err(RDNSError::MasterFileFormatError(_, _))
+        ));
+    }
+
+    #[test]
+    fn errors_are_collected_and_parsing_continues() {
+        let zone = parser::TxtConfigParser::parse_zone(
+            &mut as_lines(
+                "example.com. 60 IN A 10.0.0.1\nexample.com. 60 IN A 10.0.0.256\nexample.com. 60 IN BOGUS 1\n$BOGUS\nexample.com. 60 IN A 10.0.0.2"
+                    .to_string(),
+            ),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        let addresses = zone
+            .entries
+            .iter()
+            .map(|entry| entry.record.rdata.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["10.0.0.1", "10.0.0.2"], addresses);
+
+        let errors = zone
+            .diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.position.clone().unwrap().line)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![2, 3, 4], errors);
+        assert!(zone.has_errors());
+    }
+
+    #[test]
+    fn error_in_multi_line_record_skips_the_whole_record() {
+        let zone = parser::TxtConfigParser::parse_zone(
+            &mut as_lines(
+                "example.com. 60 IN SOA ns1 admin (\n 1 ; serial\n 2h\n 15x\n 2w\n 1h )\nexample.com. 60 IN A 10.0.0.1"
+                    .to_string(),
+            ),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        assert_eq!(1, zone.entries.len());
+        assert_eq!(
+            RData::A(rdns_core::record::AliasResourceData(Ipv4Addr::new(
+                10, 0, 0, 1
+            ))),
+            zone.entries[0].record.rdata
+        );
+        assert_eq!(1, zone.diagnostics.len());
+        assert_eq!(
+            Some(LineCharPos { line: 4, char: 2 }),
+            zone.diagnostics[0].position
+        );
+    }
+
+    #[test]
+    fn error_after_multi_line_record_skips_only_its_line() {
+        let zone = parser::TxtConfigParser::parse_zone(
+            &mut as_lines(
+                "example.com. 60 IN SOA ns1 admin ( 1 2 3 4 5 )
+bad.example.com. 60 IN A 10.0.0.256
+ok.example.com. 60 IN A 10.0.0.1
+ok2.example.com. 60 IN A 10.0.0.2"
+                    .to_string(),
+            ),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        let names = zone
+            .entries
+            .iter()
+            .map(|entry| entry.record.name.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec!["example.com.", "ok.example.com.", "ok2.example.com."],
+            names
+        );
+        assert_eq!(1, zone.diagnostics.len());
+        assert_eq!(
+            Some(LineCharPos { line: 2, char: 21 }),
+            zone.diagnostics[0].position
+        );
+    }
+
+    #[test]
+    fn error_shows_the_source_line_with_a_caret() {
+        let zone = parser::TxtConfigParser::parse_zone(
+            &mut as_lines("www\t60 IN A 10.0.0.256".to_string()),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            "1:21: error: Invalid part of IP address\n1 | www\t60 IN A 10.0.0.256\n  |    \t                ^",
+            zone.diagnostics[0].to_string()
+        );
+    }
+
+    #[test]
+    fn record_without_owner_is_reported_and_parsing_continues() {
+        let zone = TxtZoneParser::new()
+            .parse_str("  60 IN A 10.0.0.1\nexample.com. 60 IN A 10.0.0.2\n  60 IN A 10.0.0.3")
+            .unwrap();
+
+        let addresses = zone
+            .entries
+            .iter()
+            .map(|entry| entry.record.rdata.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["10.0.0.2", "10.0.0.3"], addresses);
+        assert_eq!(1, zone.diagnostics.len());
+        assert_eq!(
+            "1:3: error: record has no owner name and there is no previous owner\n1 |   60 IN A 10.0.0.1\n  |   ^",
+            zone.diagnostics[0].to_string()
+        );
+    }
+
+    #[test]
+    fn first_error_stops_parse() {
+        let result = parser::TxtConfigParser::parse(
+            &mut as_lines(
+                "example.com. 60 IN A 10.0.0.256\nexample.com. 60 IN BOGUS 1".to_string(),
+            ),
+            rdns_core::name::Name::root(),
+        );
+
+        assert!(matches!(
+            result,
+            Err(RDNSError::MasterFileFormatError(
+                _,
+                LineCharPos { line: 1, .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn generate_ptr_records() {
+        let records = parser::TxtConfigParser::parse(
+            &mut as_lines(
+                "$ORIGIN 0.0.10.in-addr.arpa.\n$GENERATE 1-3 $ 300 IN PTR host-$.example.com.\n"
+                    .to_string(),
+            ),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        let records = records
+            .iter()
+            .map(|record| record.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "1.0.0.10.in-addr.arpa. 300 IN PTR host-1.example.com.",
+                "2.0.0.10.in-addr.arpa. 300 IN PTR host-2.example.com.",
+                "3.0.0.10.in-addr.arpa. 300 IN PTR host-3.example.com.",
+            ],
+            records
+        );
+    }
+
+    #[test]
+    fn generate_with_modifiers_and_step() {
+        let records = parser::TxtConfigParser::parse(
+            &mut as_lines(
+                "$ORIGIN example.com.\n$TTL 60\n$GENERATE 0-20/10 dhcp-${100,4} IN A 10.0.1.${100}\n$GENERATE 10-11 ${0,2,x} CNAME host-${0,3,X}\n$GENERATE 255-255 ${0,0,n}.ip6 AAAA 2001:db8::${0,4,x}"
+                    .to_string(),
+            ),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        let records = records
+            .iter()
+            .map(|record| record.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "dhcp-0100.example.com. 60 IN A 10.0.1.100",
+                "dhcp-0110.example.com. 60 IN A 10.0.1.110",
+                "dhcp-0120.example.com. 60 IN A 10.0.1.120",
+                "0a.example.com. 60 IN CNAME host-00A.example.com.",
+                "0b.example.com. 60 IN CNAME host-00B.example.com.",
+                "f.f.ip6.example.com. 60 IN AAAA 2001:db8::ff",
+            ],
+            records
+        );
+    }
+
+    #[test]
+    fn generate_records_have_the_directive_position() {
+        let zone = parser::TxtConfigParser::parse_zone(
+            &mut as_lines(
+                "example.com. 60 IN A 10.0.0.1\n$GENERATE 1-100 host$.example.com. IN A 10.0.0.$"
+                    .to_string(),
+            ),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        assert_eq!(101, zone.entries.len());
+        assert!(zone.entries[1..]
+            .iter()
+            .all(|entry| entry.position == LineCharPos { line: 2, char: 1 }));
+        assert_eq!(
+            vec![Diagnostic::warning(
+                "no TTL or $TTL given, using the previous TTL 60 set on line 1".to_string(),
+                Some(LineCharPos { line: 2, char: 1 })
+            )],
+            zone.diagnostics
+        );
+    }
+
+    #[test]
+    fn generate_invalid_record() {
+        let result = parser::TxtConfigParser::parse(
+            &mut as_lines("$GENERATE 250-260 host$. 60 IN A 10.0.0.$".to_string()),
+            rdns_core::name::Name::root(),
+        );
+
+        match result {
+            Err(RDNSError::MasterFileFormatError(message, position)) => {
+                assert!(message.starts_with(
+                    "$GENERATE created an invalid record [host256. 60 IN A 10.0.0.256]"
+                ));
+                assert_eq!(LineCharPos { line: 1, char: 1 }, position);
+            }
+            result => panic!("expected a format error but got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn generate_invalid_record_adds_none_of_its_records() {
+        let zone = parser::TxtConfigParser::parse_zone(
+            &mut as_lines(
+                "$GENERATE 250-260 host$. 60 IN A 10.0.0.$\nok. 60 IN A 10.0.0.1".to_string(),
+            ),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        let names = zone
+            .entries
+            .iter()
+            .map(|entry| entry.record.name.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["ok."], names);
+        assert_eq!(1, zone.diagnostics.len());
+    }
+
+    #[test]
+    fn generate_keeps_escaped_dollar() {
+        let records = parser::TxtConfigParser::parse(
+            &mut as_lines("$GENERATE 1-1 h\\$$. 60 IN TXT \"\\$$\"".to_string()),
+            rdns_core::name::Name::root(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec!["h\\$1. 60 IN TXT \"$1\""],
+            records
+                .iter()
+                .map(|record| record.to_string())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn generate_range_over_limit() {
+        let result = parser::TxtConfigParser::parse(
+            &mut as_lines("$GENERATE 0-10000000 host$. 60 IN A 10.0.0.1".to_string()),
+            rdns_core::name::Name::root(),
+        );
+
+        assert!(matches!(
+            result,
+            Err(RDNSError::MasterFileFormatError(message, _)) if message.contains("more than the limit")
+        ));
+    }
+
+    #[test]
+    fn parse_str_with_origin_and_class() {
+        let zone = TxtZoneParser::new()
+            .with_origin(test_name("example.com."))
+            .with_class(rdns_core::RRClass::IN)
+            .parse_str("@ 60 SOA ns1 admin 1 2h 15m 2w 1h\n  60 NS ns1\nns1 60 A 10.0.0.53")
+            .unwrap();
+
+        let records = zone
+            .entries
+            .iter()
+            .map(|entry| entry.record.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "example.com. 60 IN SOA ns1.example.com. admin.example.com. 1 7200 900 1209600 3600",
+                "example.com. 60 IN NS ns1.example.com.",
+                "ns1.example.com. 60 IN A 10.0.0.53",
+            ],
+            records
+        );
+        assert_eq!(None, zone.entries[0].file);
+    }
+
+    #[test]
+    fn parse_with_class_rejects_other_classes() {
+        let zone = TxtZoneParser::new()
+            .with_class(rdns_core::RRClass::IN)
+            .parse_str("example.com. 60 CH A 10.0.0.1")
+            .unwrap();
+
+        assert!(zone.entries.is_empty());
+        assert!(zone.has_errors());
+    }
+
+    #[test]
+    fn parse_reader() {
+        let reader = std::io::BufReader::new("www 60 IN A 10.0.0.1\n".as_bytes());
+
+        let zone = TxtZoneParser::new()
+            .with_origin(test_name("example.com."))
+            .parse_reader(reader)
+            .unwrap();
+
+        assert_eq!(1, zone.entries.len());
+        assert_eq!(test_name("www.example.com."), zone.entries[0].record.name);
+    }
+
+    #[test]
+    fn parse_bytes() {
+        let zone = TxtZoneParser::new()
+            .parse_bytes(b"example.com. 60 IN A 10.0.0.1\nexample.com. 60 IN A 10.0.0.2")
+            .unwrap();
+
+        assert_eq!(2, zone.entries.len());
+    }
+
+    #[test]
+    fn parse_bytes_which_are_not_utf8() {
+        let zone = TxtZoneParser::new()
+            .parse_bytes(
+                b"example.com. 60 IN A 10.0.0.1\nexample.com. 60 IN TXT \"\xff\"\nexample.com. 60 IN A 10.0.0.2",
+            )
+            .unwrap();
+
+        assert_eq!(2, zone.entries.len());
+        assert_eq!(
+            vec![Diagnostic::error(
+                "the line is not valid UTF-8".to_string(),
+                Some(LineCharPos { line: 2, char: 1 })
+            )],
+            zone.diagnostics
+        );
+    }
+
+    #[test]
+    fn multi_line_record_with_a_line_which_is_not_utf8() {
+        let zone = TxtZoneParser::new()
+            .parse_bytes(
+                b"\xff example.com. 60 IN A 10.0.0.1\nexample.com. 60 IN SOA ns1 admin (\n 1 2\n \xff 3\n 4 5 )\nexample.com. 60 IN A 10.0.0.2",
+            )
+            .unwrap();
+
+        assert_eq!(1, zone.entries.len());
+        assert_eq!(
+            vec![1, 4],
+            zone.diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.position.clone().unwrap().line)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn parse_file() {
+        let zone = TxtZoneParser::new()
+            .with_origin(test_name("example.com."))
+            .parse_file(include_test_file("main.zone"))
+            .unwrap();
+
+        assert_eq!(test_name("example.com."), zone.entries[0].record.name);
+        assert_eq!(
+            test_name("www.hosts.example.com."),
+            zone.entries[2].record.name
+        );
+        assert_eq!(Some(include_test_file("main.zone")), zone.entries[0].file);
+    }
+
+    fn include_test_file(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("testdata/include")
+            .join(name)
+    }
+
+    fn as_lines(input: String) -> Lines<Cursor<String>> {
+        Cursor::new(input).lines()
+    }
+
+    fn test_name(name: &str) -> rdns_core::name::Name {
+        rdns_core::name::Name::parse(
+            &mut name.to_string().into_bytes().into_iter().peekable(),
+            HashSet::new(),
+        )
+        .unwrap()
     }
 }
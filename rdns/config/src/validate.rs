@@ -0,0 +1,440 @@
+use crate::diagnostic::Diagnostic;
+use crate::txt_config::ZoneEntry;
+use rdns_core::name::Name;
+use rdns_core::record::{RData, ResourceData};
+use rdns_core::RRType;
+use std::collections::HashMap;
+
+/// Checks the records loaded from a zone file for problems which would stop the zone from being
+/// served correctly. The zone apex is taken to be the owner of the first SOA record.
+pub fn validate_zone(entries: &[ZoneEntry]) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    let soas = entries
+        .iter()
+        .filter(|entry| entry.record.rr_type() == RRType::SOA)
+        .collect::<Vec<_>>();
+
+    let apex = match (soas.first(), entries.first()) {
+        (Some(soa), _) => soa.record.name.clone(),
+        (None, Some(first)) => {
+            diagnostics.push(Diagnostic::error(
+                "the zone has no SOA record".to_string(),
+                None,
+            ));
+            first.record.name.clone()
+        }
+        (None, None) => {
+            diagnostics.push(Diagnostic::error(
+                "the zone has no records".to_string(),
+                None,
+            ));
+            return diagnostics;
+        }
+    };
+
+    for soa in soas.iter().skip(1) {
+        let message = if soa.record.name == apex {
+            "the zone has more than one SOA record".to_string()
+        } else {
+            format!(
+                "SOA record for {} is not at the zone apex {}",
+                soa.record.name, apex
+            )
+        };
+        diagnostics
+            .push(Diagnostic::error(message, Some(soa.position.clone())).in_file(soa.file.clone()));
+    }
+
+    let mut by_name: HashMap<&Name, Vec<&ZoneEntry>> = HashMap::new();
+    for entry in entries {
+        by_name.entry(&entry.record.name).or_default().push(entry);
+    }
+    let owns_type = |name: &Name, rr_type: RRType<u16>| {
+        by_name
+            .get(name)
+            .map(|entries| entries.iter().any(|e| e.record.rr_type() == rr_type))
+            .unwrap_or(false)
+    };
+
+    if !owns_type(&apex, RRType::NS) {
+        diagnostics.push(Diagnostic::error(
+            format!("the zone apex {} has no NS records", apex),
+            None,
+        ));
+    }
+
+    for entry in entries {
+        if !entry.record.name.is_subdomain_of(&apex) {
+            diagnostics.push(
+                Diagnostic::error(
+                    format!(
+                        "record for {} is outside of the zone {}",
+                        entry.record.name, apex
+                    ),
+                    Some(entry.position.clone()),
+                )
+                .in_file(entry.file.clone()),
+            );
+        }
+    }
+
+    check_cnames(&by_name, &mut diagnostics);
+
+    for entry in entries {
+        match &entry.record.rdata {
+            RData::NS(target) => {
+                if owns_type(&target.0, RRType::CNAME) {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            format!("name server {} is an alias (CNAME)", target.0),
+                            Some(entry.position.clone()),
+                        )
+                        .in_file(entry.file.clone()),
+                    );
+                } else if target.0.is_subdomain_of(&apex)
+                    && !owns_type(&target.0, RRType::A)
+                    && !owns_type(&target.0, RRType::AAAA)
+                {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            format!(
+                                "name server {} is in the zone but has no address records (glue)",
+                                target.0
+                            ),
+                            Some(entry.position.clone()),
+                        )
+                        .in_file(entry.file.clone()),
+                    );
+                }
+            }
+            RData::MX(mx) if owns_type(&mx.exchange, RRType::CNAME) => {
+                diagnostics.push(
+                    Diagnostic::error(
+                        format!("mail exchange {} is an alias (CNAME)", mx.exchange),
+                        Some(entry.position.clone()),
+                    )
+                    .in_file(entry.file.clone()),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(RData::SOA(soa)) = soas.first().map(|entry| &entry.record.rdata) {
+        for entry in entries {
+            if entry.record.ttl > soa.expire {
+                diagnostics.push(
+                    Diagnostic::warning(
+                        format!(
+                            "TTL {} is longer than the SOA expire time {}",
+                            entry.record.ttl, soa.expire
+                        ),
+                        Some(entry.position.clone()),
+                    )
+                    .in_file(entry.file.clone()),
+                );
+            }
+        }
+    }
+
+    check_duplicates(entries, &mut diagnostics);
+
+    diagnostics.sort_by_key(|d| {
+        (
+            d.file.clone(),
+            d.position.as_ref().map(|p| (p.line, p.char)),
+        )
+    });
+    diagnostics
+}
+
+/// A name with a CNAME record must not have any other data (RFC 1034 section 3.6.2)
+fn check_cnames(by_name: &HashMap<&Name, Vec<&ZoneEntry>>, diagnostics: &mut Vec<Diagnostic>) {
+    for (name, entries) in by_name {
+        let (cnames, others): (Vec<&ZoneEntry>, Vec<&ZoneEntry>) = entries
+            .iter()
+            .partition(|entry| entry.record.rr_type() == RRType::CNAME);
+
+        for cname in cnames.iter().skip(1) {
+            diagnostics.push(
+                Diagnostic::error(
+                    format!("{} has more than one CNAME record", name),
+                    Some(cname.position.clone()),
+                )
+                .in_file(cname.file.clone()),
+            );
+        }
+
+        if let (Some(cname), false) = (cnames.first(), others.is_empty()) {
+            diagnostics.push(
+                Diagnostic::error(
+                    format!(
+                        "{} has a CNAME record so must not have any other data",
+                        name
+                    ),
+                    Some(cname.position.clone()),
+                )
+                .in_file(cname.file.clone()),
+            );
+        }
+    }
+}
+
+fn check_duplicates(entries: &[ZoneEntry], diagnostics: &mut Vec<Diagnostic>) {
+    // Records are grouped on their wire data with ASCII letters lowercased, since names in the
+    // data compare without regard to case. Data which only differs in the case of a string still
+    // shares a group, so each candidate in it is compared in full.
+    let mut seen: HashMap<(&Name, u16, u16, Vec<u8>), Vec<&ZoneEntry>> = HashMap::new();
+
+    for entry in entries {
+        let record = &entry.record;
+        let key = (
+            &record.name,
+            record.class.value(),
+            record.rr_type().value(),
+            record.rdata.serialise().to_ascii_lowercase(),
+        );
+        let group = seen.entry(key).or_default();
+
+        match group
+            .iter()
+            .find(|other| other.record.rdata == record.rdata)
+        {
+            Some(first) => diagnostics.push(
+                Diagnostic::warning(
+                    format!("duplicate record, first seen at {}", first.position),
+                    Some(entry.position.clone()),
+                )
+                .in_file(entry.file.clone()),
+            ),
+            None => group.push(entry),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diagnostic::{Diagnostic, Severity};
+    use crate::txt_config::{TxtZoneParser, ZoneEntry};
+    use crate::validate::validate_zone;
+    use rdns_core::error::LineCharPos;
+    use rdns_core::RRClass;
+
+    const VALID_ZONE: &str = "\
+$ORIGIN example.com.
+$TTL 300
+@        SOA  ns1 admin 1 7200 600 3600000 60
+         NS   ns1
+ns1      A    10.0.0.53
+www      A    10.0.0.1
+@        MX   10 www
+";
+
+    #[test]
+    fn valid_zone() {
+        assert!(validate_zone(&parse(VALID_ZONE)).is_empty());
+    }
+
+    #[test]
+    fn no_records() {
+        let diagnostics = validate_zone(&parse("$ORIGIN example.com.\n"));
+        assert_eq!(
+            vec![Diagnostic::error(
+                "the zone has no records".to_string(),
+                None
+            )],
+            diagnostics
+        );
+    }
+
+    #[test]
+    fn missing_soa() {
+        let diagnostics = validate_zone(&parse(
+            "$ORIGIN example.com.\n@ 300 NS ns1\nns1 300 A 10.0.0.53\n",
+        ));
+        assert_eq!(
+            vec![Diagnostic::error(
+                "the zone has no SOA record".to_string(),
+                None
+            )],
+            diagnostics
+        );
+    }
+
+    #[test]
+    fn more_than_one_soa() {
+        let diagnostics = validate_zone(&parse(&format!(
+            "{}@ SOA ns1 admin 2 7200 600 3600000 60\nwww SOA ns1 admin 3 7200 600 3600000 60\n",
+            VALID_ZONE
+        )));
+        assert_eq!(
+            vec![
+                error(8, 1, "the zone has more than one SOA record"),
+                error(
+                    9,
+                    1,
+                    "SOA record for www.example.com. is not at the zone apex example.com."
+                ),
+            ],
+            without_warnings(diagnostics)
+        );
+    }
+
+    #[test]
+    fn missing_apex_ns() {
+        let diagnostics = validate_zone(&parse(
+            "$ORIGIN example.com.\n@ 300 SOA ns1 admin 1 7200 600 3600000 60\n",
+        ));
+        assert_eq!(
+            vec![Diagnostic::error(
+                "the zone apex example.com. has no NS records".to_string(),
+                None
+            )],
+            diagnostics
+        );
+    }
+
+    #[test]
+    fn cname_with_other_data() {
+        let diagnostics = validate_zone(&parse(&format!(
+            "{}host A 10.0.0.7\n     CNAME @\n     CNAME ns1\n",
+            VALID_ZONE
+        )));
+        assert_eq!(
+            vec![
+                error(
+                    9,
+                    6,
+                    "host.example.com. has a CNAME record so must not have any other data"
+                ),
+                error(10, 6, "host.example.com. has more than one CNAME record"),
+            ],
+            diagnostics
+        );
+    }
+
+    #[test]
+    fn out_of_zone_record() {
+        let diagnostics = validate_zone(&parse(&format!(
+            "{}www.example.org. A 10.0.0.1\n",
+            VALID_ZONE
+        )));
+        assert_eq!(
+            vec![error(
+                8,
+                1,
+                "record for www.example.org. is outside of the zone example.com."
+            )],
+            diagnostics
+        );
+    }
+
+    #[test]
+    fn missing_glue() {
+        let diagnostics = validate_zone(&parse(&format!(
+            "{}sub NS ns1.sub\nother NS ns1.example.org.\n",
+            VALID_ZONE
+        )));
+        assert_eq!(
+            vec![error(
+                8,
+                1,
+                "name server ns1.sub.example.com. is in the zone but has no address records (glue)"
+            )],
+            diagnostics
+        );
+    }
+
+    #[test]
+    fn name_server_and_mail_exchange_aliases() {
+        let diagnostics = validate_zone(&parse(&format!(
+            "{}alias CNAME www\n@ NS alias\n@ MX 10 alias\n",
+            VALID_ZONE
+        )));
+        assert_eq!(
+            vec![
+                error(9, 1, "name server alias.example.com. is an alias (CNAME)"),
+                error(
+                    10,
+                    1,
+                    "mail exchange alias.example.com. is an alias (CNAME)"
+                ),
+            ],
+            diagnostics
+        );
+    }
+
+    #[test]
+    fn ttl_above_soa_expire() {
+        let diagnostics =
+            validate_zone(&parse(&format!("{}long 3600001 A 10.0.0.5\n", VALID_ZONE)));
+        assert_eq!(
+            vec![Diagnostic::warning(
+                "TTL 3600001 is longer than the SOA expire time 3600000".to_string(),
+                Some(LineCharPos { line: 8, char: 1 })
+            )],
+            diagnostics
+        );
+    }
+
+    #[test]
+    fn duplicate_records() {
+        let diagnostics = validate_zone(&parse(&format!("{}WWW A 10.0.0.1\n", VALID_ZONE)));
+        assert_eq!(
+            vec![Diagnostic::warning(
+                "duplicate record, first seen at 6:1".to_string(),
+                Some(LineCharPos { line: 8, char: 1 })
+            )],
+            diagnostics
+        );
+    }
+
+    #[test]
+    fn duplicate_records_compare_names_in_data_without_case() {
+        let diagnostics = validate_zone(&parse(&format!(
+            "{}@ NS NS1.Example.COM.\n@ TXT \"v=spf1 -all\"\n@ TXT \"V=SPF1 -ALL\"\n",
+            VALID_ZONE
+        )));
+        assert_eq!(
+            vec![Diagnostic::warning(
+                "duplicate record, first seen at 4:10".to_string(),
+                Some(LineCharPos { line: 8, char: 1 })
+            )],
+            diagnostics
+        );
+    }
+
+    #[test]
+    fn display_diagnostic() {
+        assert_eq!(
+            "4:1: error: the zone has more than one SOA record",
+            error(4, 1, "the zone has more than one SOA record").to_string()
+        );
+        assert_eq!(
+            "warning: something",
+            Diagnostic::warning("something".to_string(), None).to_string()
+        );
+    }
+
+    /// The entries of a zone which parses without any diagnostics of its own
+    fn parse(zone: &str) -> Vec<ZoneEntry> {
+        let zone = TxtZoneParser::new()
+            .with_class(RRClass::IN)
+            .parse_str(zone)
+            .unwrap();
+        assert_eq!(Vec::<Diagnostic>::new(), zone.diagnostics);
+        zone.entries
+    }
+
+    fn without_warnings(diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+        diagnostics
+            .into_iter()
+            .filter(|d| d.severity == Severity::Error)
+            .collect()
+    }
+
+    fn error(line: u32, char: u32, message: &str) -> Diagnostic {
+        Diagnostic::error(message.to_string(), Some(LineCharPos { line, char }))
+    }
+}
@@ -10,19 +10,47 @@ pub enum RDNSError {
     #[error("name label exceeds the 63 byte limit")]
     NameLabelTooLong(u8),
 
+    #[error("character-string exceeds the 255 byte limit")]
+    CharacterStringTooLong(usize),
+
     #[error("name label is invalid at position {0}")]
     NameLabelInvalid(u8),
 
     #[error("the name is invalid")]
     NameInvalid(),
 
+    #[error("the name is not a valid internationalized domain name - {0}")]
+    NameIdnaInvalid(String),
+
     #[error("the resource record is invalid")]
     ResourceRecordInvalid(),
 
+    #[error("the resource record does not belong to the RRset for {0}")]
+    RRSetMismatch(String),
+
+    #[error("the resource record does not belong in the zone {0}")]
+    RecordOutOfZone(String),
+
     // TODO capture line and char position
     #[error("the format of the master file is invalid at position {1} - {0}")]
     MasterFileFormatError(String, LineCharPos),
 
+    #[error("error in {file} included at position {position}")]
+    IncludeFailed {
+        file: String,
+        position: LineCharPos,
+        source: Box<RDNSError>,
+    },
+
+    #[error("the message ended unexpectedly, it is only {0} bytes long")]
+    MessageTruncated(usize),
+
+    #[error("name compression pointer to offset {0} is invalid")]
+    NamePointerInvalid(usize),
+
+    #[error("the message is invalid - {0}")]
+    MessageFormatError(String),
+
     #[error("i/o error")]
     IoError {
         #[from]
@@ -36,7 +64,7 @@ pub enum RDNSError {
     },
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct LineCharPos {
     pub line: u32,
     pub char: u32,
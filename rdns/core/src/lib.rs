@@ -1,27 +1,117 @@
+use crate::error::RDNSError;
 use crate::name::Name;
-use std::rc::Rc;
+use crate::record::ResourceData;
+use crate::wire::{WireReader, WireWriter};
+use std::fmt::{Display, Formatter};
 
 pub mod error;
+pub mod message;
 pub mod name;
 pub mod record;
+pub mod rrset;
+pub mod ttl;
+pub mod wire;
+pub mod zone;
 
 #[cfg(test)]
 mod test;
 
-/// A resource record (RR)
-#[derive(Clone)]
+/// A resource record (RR). Records are equal when their owner names are equal ignoring case and
+/// all other fields are identical.
+#[derive(Clone, Debug, PartialEq)]
 pub struct ResourceRecord {
     /// The owner name of this resource record
     pub name: Name,
-    /// The TYPE code
-    pub rr_type: RRType<u16>,
     /// The CLASS code
     pub class: RRClass<u16>,
     /// The time interval that the resource may be cached for. A zero value means the record should
     /// not be cached.
     pub ttl: i32,
-    /// The value of the resource record
-    pub rdata: Rc<dyn record::ResourceData>,
+    /// The value of the resource record, which also determines its TYPE
+    pub rdata: record::RData,
+}
+
+impl ResourceRecord {
+    /// The TYPE code, as given by the variant of the record's data
+    pub fn rr_type(&self) -> RRType<u16> {
+        self.rdata.rr_type()
+    }
+
+    /// The presentation format of the record with the TTL and SOA timers written with units, such
+    /// as `1h30m`
+    pub fn display_with_units(&self) -> String {
+        let rdata = match &self.rdata {
+            record::RData::SOA(soa) => soa.display_with_units(),
+            rdata => rdata.to_string(),
+        };
+
+        format!(
+            "{} {} {} {} {}",
+            self.name,
+            ttl::format_signed(self.ttl),
+            self.class,
+            self.rr_type(),
+            rdata
+        )
+    }
+
+    /// Reads a resource record in wire format
+    pub fn read(reader: &mut WireReader) -> Result<Self, RDNSError> {
+        let name = Name::read(reader)?;
+        let rr_type = RRType::from_value(reader.read_u16()?);
+        let class = RRClass::from_value(reader.read_u16()?);
+        let ttl = reader.read_i32()?;
+        let rd_length = reader.read_u16()?;
+        let rdata = record::read_resource_data(&rr_type, reader, rd_length)?;
+
+        Ok(ResourceRecord {
+            name,
+            class,
+            ttl,
+            rdata,
+        })
+    }
+
+    /// Writes this resource record in wire format. The record data must fit in the 16 bit
+    /// RDLENGTH.
+    pub fn write(&self, writer: &mut WireWriter) -> Result<(), RDNSError> {
+        self.name.write(writer);
+        writer.write_u16(self.rr_type().value());
+        writer.write_u16(self.class.value());
+        writer.write_i32(self.ttl);
+
+        // RDLENGTH is only known once the data has been written, compression may shorten it
+        let rd_length_pos = writer.len();
+        writer.write_u16(0);
+        self.rdata.write(writer);
+        let rd_length = writer.len() - rd_length_pos - 2;
+        let rd_length = u16::try_from(rd_length).map_err(|_| {
+            RDNSError::MessageFormatError(format!(
+                "the {} record data is {} bytes, more than the limit of {}",
+                self.rr_type(),
+                rd_length,
+                u16::MAX
+            ))
+        })?;
+        writer.set_u16(rd_length_pos, rd_length);
+
+        Ok(())
+    }
+}
+
+/// The master file presentation format of the record, `owner TTL CLASS TYPE RDATA`
+impl Display for ResourceRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {}",
+            self.name,
+            self.ttl,
+            self.class,
+            self.rr_type(),
+            self.rdata
+        )
+    }
 }
 
 /// Resource record TYPE
@@ -59,6 +149,10 @@ pub enum RRType<T> {
     MX,
     /// Text, text strings
     TXT,
+    /// An IPv6 host address (RFC 3596)
+    AAAA,
+    /// Service locator (RFC 2782)
+    SRV,
     /// A TYPE which is not known by this implementation
     UNKNOWN(T),
 }
@@ -83,6 +177,8 @@ impl RRType<u16> {
             RRType::MINFO => 14,
             RRType::MX => 15,
             RRType::TXT => 16,
+            RRType::AAAA => 28,
+            RRType::SRV => 33,
             RRType::UNKNOWN(v) => *v,
         }
     }
@@ -106,6 +202,8 @@ impl RRType<u16> {
             14 => RRType::MINFO,
             15 => RRType::MX,
             16 => RRType::TXT,
+            28 => RRType::AAAA,
+            33 => RRType::SRV,
             v => RRType::UNKNOWN(v),
         }
     }
@@ -114,8 +212,9 @@ impl RRType<u16> {
 impl TryFrom<&str> for RRType<u16> {
     type Error = ();
 
+    /// Parses a TYPE mnemonic, or the generic `TYPEnnn` form from RFC 3597 for any TYPE code
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        Ok(match value {
+        Ok(match value.to_ascii_uppercase().as_str() {
             "A" => RRType::A,
             "NS" => RRType::NS,
             "MD" => RRType::MD,
@@ -132,11 +231,22 @@ impl TryFrom<&str> for RRType<u16> {
             "MINFO" => RRType::MINFO,
             "MX" => RRType::MX,
             "TXT" => RRType::TXT,
-            _ => RRType::UNKNOWN(0),
+            "AAAA" => RRType::AAAA,
+            "SRV" => RRType::SRV,
+            other => RRType::from_value(parse_generic_mnemonic(other, "TYPE")?),
         })
     }
 }
 
+impl Display for RRType<u16> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RRType::UNKNOWN(v) => write!(f, "TYPE{}", v),
+            _ => write!(f, "{:?}", self),
+        }
+    }
+}
+
 /// Resource record CLASS
 #[derive(Debug, Clone, PartialEq)]
 pub enum RRClass<T> {
@@ -178,20 +288,120 @@ impl RRClass<u16> {
 impl TryFrom<&str> for RRClass<u16> {
     type Error = ();
 
+    /// Parses a CLASS mnemonic, or the generic `CLASSnnn` form from RFC 3597 for any CLASS code
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        Ok(match value {
+        Ok(match value.to_ascii_uppercase().as_str() {
             "IN" => RRClass::IN,
             "CS" => RRClass::CS,
             "CH" => RRClass::CH,
             "HS" => RRClass::HS,
-            _ => RRClass::UNKNOWN(0),
+            other => RRClass::from_value(parse_generic_mnemonic(other, "CLASS")?),
         })
     }
 }
 
+impl Display for RRClass<u16> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RRClass::UNKNOWN(v) => write!(f, "CLASS{}", v),
+            _ => write!(f, "{:?}", self),
+        }
+    }
+}
+
+/// Parses the code from a generic mnemonic such as `TYPE65280` or `CLASS32`
+fn parse_generic_mnemonic(value: &str, prefix: &str) -> Result<u16, ()> {
+    let digits = value.strip_prefix(prefix).ok_or(())?;
+    if digits.is_empty() || !digits.bytes().all(|ch| ch.is_ascii_digit()) {
+        return Err(());
+    }
+
+    digits.parse::<u16>().map_err(|_| ())
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{RRClass, RRType};
+    use crate::name::Name;
+    use crate::record::{AliasResourceData, RData, RawResourceData};
+    use crate::{RRClass, RRType, ResourceRecord};
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn resource_records_are_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<ResourceRecord>();
+    }
+
+    #[test]
+    fn resource_record_equality() {
+        let record = ResourceRecord {
+            name: Name::try_from("www.example.com.".to_string()).unwrap(),
+            class: RRClass::IN,
+            ttl: 300,
+            rdata: RData::A(AliasResourceData(Ipv4Addr::new(10, 0, 0, 1))),
+        };
+
+        let mut other = record.clone();
+        other.name = Name::try_from("WWW.example.com.".to_string()).unwrap();
+        assert_eq!(record, other);
+
+        // The same data under a different TYPE is a different record
+        other.rdata = RData::unknown(65280, RawResourceData(vec![10, 0, 0, 1])).unwrap();
+        assert_ne!(record, other);
+
+        other.rdata = RData::A(AliasResourceData(Ipv4Addr::new(10, 0, 0, 2)));
+        assert_ne!(record, other);
+
+        let mut other = record.clone();
+        other.ttl = 60;
+        assert_ne!(record, other);
+    }
+
+    #[test]
+    fn display_resource_record() {
+        let record = ResourceRecord {
+            name: Name::try_from("www.example.com.".to_string()).unwrap(),
+            class: RRClass::IN,
+            ttl: 300,
+            rdata: RData::A(AliasResourceData(Ipv4Addr::new(10, 0, 0, 1))),
+        };
+
+        assert_eq!("www.example.com. 300 IN A 10.0.0.1", record.to_string());
+    }
+
+    #[test]
+    fn display_resource_record_with_ttl_units() {
+        let record = ResourceRecord {
+            name: Name::try_from("www.example.com.".to_string()).unwrap(),
+            class: RRClass::IN,
+            ttl: 5400,
+            rdata: RData::A(AliasResourceData(Ipv4Addr::new(10, 0, 0, 1))),
+        };
+
+        assert_eq!(
+            "www.example.com. 1h30m IN A 10.0.0.1",
+            record.display_with_units()
+        );
+        assert_eq!(
+            "www.example.com. -1 IN A 10.0.0.1",
+            ResourceRecord { ttl: -1, ..record }.display_with_units()
+        );
+    }
+
+    #[test]
+    fn display_unknown_resource_record() {
+        let record = ResourceRecord {
+            name: Name::try_from("example.com.".to_string()).unwrap(),
+            class: RRClass::UNKNOWN(32),
+            ttl: 60,
+            rdata: RData::unknown(65280, RawResourceData(vec![0xAB, 0xCD])).unwrap(),
+        };
+
+        assert_eq!(
+            "example.com. 60 CLASS32 TYPE65280 \\# 2 ABCD",
+            record.to_string()
+        );
+    }
 
     #[test]
     fn rr_type_round_trip() {
@@ -211,11 +421,31 @@ mod tests {
         assert_round_trip_for_rr_type(RRType::MINFO);
         assert_round_trip_for_rr_type(RRType::MX);
         assert_round_trip_for_rr_type(RRType::TXT);
+        assert_round_trip_for_rr_type(RRType::AAAA);
+        assert_round_trip_for_rr_type(RRType::SRV);
         assert_round_trip_for_rr_type(RRType::UNKNOWN(100));
     }
 
     fn assert_round_trip_for_rr_type(rr_type: RRType<u16>) {
         assert_eq!(rr_type, RRType::from_value(rr_type.value()));
+        assert_eq!(
+            Ok(rr_type.clone()),
+            RRType::try_from(rr_type.to_string().as_str())
+        );
+    }
+
+    #[test]
+    fn rr_type_mnemonics() {
+        assert_eq!(Ok(RRType::A), RRType::try_from("a"));
+        assert_eq!(Ok(RRType::MX), RRType::try_from("TYPE15"));
+        assert_eq!(Ok(RRType::UNKNOWN(65280)), RRType::try_from("type65280"));
+        assert_eq!("TYPE65280", RRType::UNKNOWN(65280).to_string());
+        assert_eq!("CNAME", RRType::CNAME.to_string());
+
+        assert_eq!(Err(()), RRType::try_from("EXAMPLE"));
+        assert_eq!(Err(()), RRType::try_from("TYPE"));
+        assert_eq!(Err(()), RRType::try_from("TYPE+1"));
+        assert_eq!(Err(()), RRType::try_from("TYPE65536"));
     }
 
     #[test]
@@ -229,5 +459,20 @@ mod tests {
 
     fn assert_round_trip_for_rr_class(rr_class: RRClass<u16>) {
         assert_eq!(rr_class, RRClass::from_value(rr_class.value()));
+        assert_eq!(
+            Ok(rr_class.clone()),
+            RRClass::try_from(rr_class.to_string().as_str())
+        );
+    }
+
+    #[test]
+    fn rr_class_mnemonics() {
+        assert_eq!(Ok(RRClass::IN), RRClass::try_from("in"));
+        assert_eq!(Ok(RRClass::CH), RRClass::try_from("CLASS3"));
+        assert_eq!(Ok(RRClass::UNKNOWN(999)), RRClass::try_from("CLASS999"));
+        assert_eq!("CLASS999", RRClass::UNKNOWN(999).to_string());
+
+        assert_eq!(Err(()), RRClass::try_from("A"));
+        assert_eq!(Err(()), RRClass::try_from("CLASS"));
     }
 }
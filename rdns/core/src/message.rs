@@ -0,0 +1,526 @@
+use crate::error::RDNSError;
+use crate::name::Name;
+use crate::wire::{WireReader, WireWriter};
+use crate::{RRClass, RRType, ResourceRecord};
+
+/// A DNS message, used for both queries and responses (RFC 1035 section 4)
+#[derive(Debug, Clone)]
+pub struct Message {
+    pub header: Header,
+    /// The questions being asked of the name server
+    pub questions: Vec<Question>,
+    /// Resource records which answer the questions
+    pub answers: Vec<ResourceRecord>,
+    /// Resource records which point toward an authoritative name server
+    pub authorities: Vec<ResourceRecord>,
+    /// Resource records which relate to the query but are not strictly answers to the questions
+    pub additionals: Vec<ResourceRecord>,
+}
+
+impl Message {
+    /// Reads a complete message in wire format
+    pub fn read(source: &[u8]) -> Result<Self, RDNSError> {
+        let mut reader = WireReader::new(source);
+
+        let header = Header::read(&mut reader)?;
+        let question_count = reader.read_u16()?;
+        let answer_count = reader.read_u16()?;
+        let authority_count = reader.read_u16()?;
+        let additional_count = reader.read_u16()?;
+
+        let questions = (0..question_count)
+            .map(|_| Question::read(&mut reader))
+            .collect::<Result<Vec<_>, _>>()?;
+        let answers = Message::read_records(&mut reader, answer_count)?;
+        let authorities = Message::read_records(&mut reader, authority_count)?;
+        let additionals = Message::read_records(&mut reader, additional_count)?;
+
+        if reader.remaining() != 0 {
+            return Err(RDNSError::MessageFormatError(format!(
+                "{} unexpected bytes after the last section",
+                reader.remaining()
+            )));
+        }
+
+        Ok(Message {
+            header,
+            questions,
+            answers,
+            authorities,
+            additionals,
+        })
+    }
+
+    fn read_records(reader: &mut WireReader, count: u16) -> Result<Vec<ResourceRecord>, RDNSError> {
+        (0..count).map(|_| ResourceRecord::read(reader)).collect()
+    }
+
+    /// The wire format of this message. The section counts in the header are taken from the
+    /// number of entries in each section, which must each fit in 16 bits, as must the length of
+    /// each record's data.
+    pub fn serialise(&self) -> Result<Vec<u8>, RDNSError> {
+        let mut writer = WireWriter::new();
+
+        self.header.write(&mut writer);
+        writer.write_u16(Message::section_count("question", self.questions.len())?);
+        writer.write_u16(Message::section_count("answer", self.answers.len())?);
+        writer.write_u16(Message::section_count("authority", self.authorities.len())?);
+        writer.write_u16(Message::section_count(
+            "additional",
+            self.additionals.len(),
+        )?);
+
+        for question in &self.questions {
+            question.write(&mut writer);
+        }
+        for record in self
+            .answers
+            .iter()
+            .chain(self.authorities.iter())
+            .chain(self.additionals.iter())
+        {
+            record.write(&mut writer)?;
+        }
+
+        Ok(writer.into_bytes())
+    }
+
+    fn section_count(section: &str, count: usize) -> Result<u16, RDNSError> {
+        u16::try_from(count).map_err(|_| {
+            RDNSError::MessageFormatError(format!(
+                "the {} section has {} entries, more than the limit of {}",
+                section,
+                count,
+                u16::MAX
+            ))
+        })
+    }
+}
+
+/// The message header, excluding the section counts which are derived from the message content
+#[derive(Debug, Clone, PartialEq)]
+pub struct Header {
+    /// Identifier assigned by the requester and copied into the response
+    pub id: u16,
+    /// Whether this message is a response (QR), otherwise it is a query
+    pub response: bool,
+    /// The kind of query
+    pub opcode: Opcode,
+    /// Authoritative Answer (AA), whether the responding name server is an authority for the
+    /// domain name in the question
+    pub authoritative_answer: bool,
+    /// TrunCation (TC), whether this message was truncated to fit the transmission channel
+    pub truncated: bool,
+    /// Recursion Desired (RD), set in a query and copied into the response
+    pub recursion_desired: bool,
+    /// Recursion Available (RA), whether the name server supports recursive queries
+    pub recursion_available: bool,
+    /// Reserved for future use, the three bits should be zero
+    pub z: u8,
+    /// The response code (RCODE)
+    pub response_code: ResponseCode,
+}
+
+impl Header {
+    fn read(reader: &mut WireReader) -> Result<Self, RDNSError> {
+        let id = reader.read_u16()?;
+        let flags = reader.read_u16()?;
+
+        Ok(Header {
+            id,
+            response: flags & 0x8000 != 0,
+            opcode: Opcode::from_value(((flags >> 11) & 0x0F) as u8),
+            authoritative_answer: flags & 0x0400 != 0,
+            truncated: flags & 0x0200 != 0,
+            recursion_desired: flags & 0x0100 != 0,
+            recursion_available: flags & 0x0080 != 0,
+            z: ((flags >> 4) & 0x07) as u8,
+            response_code: ResponseCode::from_value((flags & 0x0F) as u8),
+        })
+    }
+
+    fn write(&self, writer: &mut WireWriter) {
+        let mut flags = 0u16;
+        if self.response {
+            flags |= 0x8000;
+        }
+        flags |= ((self.opcode.value() & 0x0F) as u16) << 11;
+        if self.authoritative_answer {
+            flags |= 0x0400;
+        }
+        if self.truncated {
+            flags |= 0x0200;
+        }
+        if self.recursion_desired {
+            flags |= 0x0100;
+        }
+        if self.recursion_available {
+            flags |= 0x0080;
+        }
+        flags |= ((self.z & 0x07) as u16) << 4;
+        flags |= (self.response_code.value() & 0x0F) as u16;
+
+        writer.write_u16(self.id);
+        writer.write_u16(flags);
+    }
+}
+
+/// An entry in the question section
+#[derive(Debug, Clone)]
+pub struct Question {
+    /// The domain name being queried
+    pub name: Name,
+    /// The TYPE of the query (QTYPE)
+    pub q_type: RRType<u16>,
+    /// The CLASS of the query (QCLASS)
+    pub q_class: RRClass<u16>,
+}
+
+impl Question {
+    fn read(reader: &mut WireReader) -> Result<Self, RDNSError> {
+        Ok(Question {
+            name: Name::read(reader)?,
+            q_type: RRType::from_value(reader.read_u16()?),
+            q_class: RRClass::from_value(reader.read_u16()?),
+        })
+    }
+
+    fn write(&self, writer: &mut WireWriter) {
+        self.name.write(writer);
+        writer.write_u16(self.q_type.value());
+        writer.write_u16(self.q_class.value());
+    }
+}
+
+/// The kind of query in a message
+#[derive(Debug, Clone, PartialEq)]
+pub enum Opcode {
+    /// A standard query
+    QUERY,
+    /// An inverse query, OBSOLETE
+    IQUERY,
+    /// A server status request
+    STATUS,
+    /// An opcode which is not known by this implementation
+    UNKNOWN(u8),
+}
+
+impl Opcode {
+    /// The four bit OPCODE value
+    pub fn value(&self) -> u8 {
+        match self {
+            Opcode::QUERY => 0,
+            Opcode::IQUERY => 1,
+            Opcode::STATUS => 2,
+            Opcode::UNKNOWN(v) => *v,
+        }
+    }
+
+    pub fn from_value(value: u8) -> Self {
+        match value {
+            0 => Opcode::QUERY,
+            1 => Opcode::IQUERY,
+            2 => Opcode::STATUS,
+            v => Opcode::UNKNOWN(v),
+        }
+    }
+}
+
+/// The outcome of a query, set in responses
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResponseCode {
+    /// No error condition
+    NOERROR,
+    /// Format error, the name server was unable to interpret the query
+    FORMERR,
+    /// Server failure, the name server was unable to process the query
+    SERVFAIL,
+    /// Name error, the domain name referenced in the query does not exist
+    NXDOMAIN,
+    /// Not implemented, the name server does not support the kind of query
+    NOTIMP,
+    /// Refused, the name server refuses to perform the operation for policy reasons
+    REFUSED,
+    /// A response code which is not known by this implementation
+    UNKNOWN(u8),
+}
+
+impl ResponseCode {
+    /// The four bit RCODE value
+    pub fn value(&self) -> u8 {
+        match self {
+            ResponseCode::NOERROR => 0,
+            ResponseCode::FORMERR => 1,
+            ResponseCode::SERVFAIL => 2,
+            ResponseCode::NXDOMAIN => 3,
+            ResponseCode::NOTIMP => 4,
+            ResponseCode::REFUSED => 5,
+            ResponseCode::UNKNOWN(v) => *v,
+        }
+    }
+
+    pub fn from_value(value: u8) -> Self {
+        match value {
+            0 => ResponseCode::NOERROR,
+            1 => ResponseCode::FORMERR,
+            2 => ResponseCode::SERVFAIL,
+            3 => ResponseCode::NXDOMAIN,
+            4 => ResponseCode::NOTIMP,
+            5 => ResponseCode::REFUSED,
+            v => ResponseCode::UNKNOWN(v),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::RDNSError;
+    use crate::message::{Header, Message, Opcode, Question, ResponseCode};
+    use crate::name::Name;
+    use crate::record::{AliasResourceData, MailExchangeResourceData, NullResourceData, RData};
+    use crate::{test, RRClass, RRType, ResourceRecord};
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn read_query() {
+        let mut input = vec![
+            0x12, 0x34, // ID
+            0x01, 0x00, // RD
+            0x00, 0x01, // QDCOUNT
+            0x00, 0x00, // ANCOUNT
+            0x00, 0x00, // NSCOUNT
+            0x00, 0x00, // ARCOUNT
+        ];
+        input.extend(test::dirty_to_bytes("example.com.".to_string()));
+        input.extend_from_slice(&[0x00, 0x01, 0x00, 0x01]);
+
+        let message = Message::read(&input).unwrap();
+
+        assert_eq!(0x1234, message.header.id);
+        assert!(!message.header.response);
+        assert!(message.header.recursion_desired);
+        assert_eq!(Opcode::QUERY, message.header.opcode);
+        assert_eq!(ResponseCode::NOERROR, message.header.response_code);
+        assert_eq!(1, message.questions.len());
+        assert_eq!(RRType::A, message.questions[0].q_type);
+        assert_eq!(RRClass::IN, message.questions[0].q_class);
+        assert_eq!(
+            "example.com.",
+            <Name as Into<String>>::into(message.questions[0].name.clone())
+        );
+
+        assert_eq!(input, message.serialise().unwrap());
+    }
+
+    #[test]
+    fn round_trip_header_flags() {
+        let header = Header {
+            id: 0xBEEF,
+            response: true,
+            opcode: Opcode::STATUS,
+            authoritative_answer: true,
+            truncated: true,
+            recursion_desired: false,
+            recursion_available: true,
+            z: 0,
+            response_code: ResponseCode::NXDOMAIN,
+        };
+        let message = Message {
+            header: header.clone(),
+            questions: Vec::new(),
+            answers: Vec::new(),
+            authorities: Vec::new(),
+            additionals: Vec::new(),
+        };
+
+        let bytes = message.serialise().unwrap();
+        assert_eq!(12, bytes.len());
+        assert_eq!(header, Message::read(&bytes).unwrap().header);
+    }
+
+    #[test]
+    fn round_trip_response_with_answer() {
+        let name = Name::try_from("example.com.".to_string()).unwrap();
+        let message = Message {
+            header: Header {
+                id: 1,
+                response: true,
+                opcode: Opcode::QUERY,
+                authoritative_answer: true,
+                truncated: false,
+                recursion_desired: true,
+                recursion_available: false,
+                z: 0,
+                response_code: ResponseCode::NOERROR,
+            },
+            questions: vec![Question {
+                name: name.clone(),
+                q_type: RRType::A,
+                q_class: RRClass::IN,
+            }],
+            answers: vec![ResourceRecord {
+                name,
+                class: RRClass::IN,
+                ttl: 300,
+                rdata: RData::A(AliasResourceData(Ipv4Addr::new(10, 0, 0, 1))),
+            }],
+            authorities: Vec::new(),
+            additionals: Vec::new(),
+        };
+
+        let bytes = message.serialise().unwrap();
+        let read = Message::read(&bytes).unwrap();
+
+        assert_eq!(1, read.questions.len());
+        assert_eq!(1, read.answers.len());
+        assert_eq!(0, read.authorities.len());
+        assert_eq!(0, read.additionals.len());
+
+        let answer = &read.answers[0];
+        assert_eq!(RRType::A, answer.rr_type());
+        assert_eq!(RRClass::IN, answer.class);
+        assert_eq!(300, answer.ttl);
+        assert_eq!(
+            RData::A(AliasResourceData(Ipv4Addr::new(10, 0, 0, 1))),
+            answer.rdata
+        );
+
+        assert_eq!(bytes, read.serialise().unwrap());
+    }
+
+    #[test]
+    fn compress_names_across_sections() {
+        let name = Name::try_from("example.com.".to_string()).unwrap();
+        let message = Message {
+            header: Header {
+                id: 1,
+                response: true,
+                opcode: Opcode::QUERY,
+                authoritative_answer: true,
+                truncated: false,
+                recursion_desired: false,
+                recursion_available: false,
+                z: 0,
+                response_code: ResponseCode::NOERROR,
+            },
+            questions: vec![Question {
+                name: name.clone(),
+                q_type: RRType::MX,
+                q_class: RRClass::IN,
+            }],
+            answers: vec![ResourceRecord {
+                name,
+                class: RRClass::IN,
+                ttl: 300,
+                rdata: RData::MX(MailExchangeResourceData {
+                    preference: 10,
+                    exchange: Name::try_from("mail.example.com.".to_string()).unwrap(),
+                }),
+            }],
+            authorities: Vec::new(),
+            additionals: Vec::new(),
+        };
+
+        let bytes = message.serialise().unwrap();
+
+        let expected_answer = vec![
+            0xC0, 12, // owner points to the question name
+            0, 15, 0, 1, 0, 0, 1, 44, // TYPE, CLASS and TTL
+            0, 9, // RDLENGTH
+            0, 10, // preference
+            4, b'm', b'a', b'i', b'l', 0xC0, 12, // exchange
+        ];
+        // The answer follows the header, the question name and the QTYPE and QCLASS
+        assert_eq!(expected_answer, bytes[12 + 13 + 4..].to_vec());
+
+        let read = Message::read(&bytes).unwrap();
+        assert_eq!(
+            "example.com.",
+            <Name as Into<String>>::into(read.answers[0].name.clone())
+        );
+    }
+
+    #[test]
+    fn serialise_too_many_questions() {
+        let question = Question {
+            name: Name::try_from("example.com.".to_string()).unwrap(),
+            q_type: RRType::A,
+            q_class: RRClass::IN,
+        };
+        let message = Message {
+            header: Header {
+                id: 1,
+                response: false,
+                opcode: Opcode::QUERY,
+                authoritative_answer: false,
+                truncated: false,
+                recursion_desired: true,
+                recursion_available: false,
+                z: 0,
+                response_code: ResponseCode::NOERROR,
+            },
+            questions: vec![question; 65536],
+            answers: Vec::new(),
+            authorities: Vec::new(),
+            additionals: Vec::new(),
+        };
+
+        let err = message.serialise().unwrap_err();
+        assert!(matches!(err, RDNSError::MessageFormatError(_)));
+    }
+
+    #[test]
+    fn serialise_record_data_too_long() {
+        let record = ResourceRecord {
+            name: Name::try_from("example.com.".to_string()).unwrap(),
+            class: RRClass::IN,
+            ttl: 60,
+            rdata: RData::NULL(NullResourceData(vec![0; 65536])),
+        };
+        let message = Message {
+            header: Header {
+                id: 1,
+                response: true,
+                opcode: Opcode::QUERY,
+                authoritative_answer: true,
+                truncated: false,
+                recursion_desired: false,
+                recursion_available: false,
+                z: 0,
+                response_code: ResponseCode::NOERROR,
+            },
+            questions: Vec::new(),
+            answers: vec![record],
+            authorities: Vec::new(),
+            additionals: Vec::new(),
+        };
+
+        let err = message.serialise().unwrap_err();
+        assert!(matches!(err, RDNSError::MessageFormatError(_)));
+    }
+
+    #[test]
+    fn read_truncated_header() {
+        let err = Message::read(&[0x12, 0x34, 0x01]).err().unwrap();
+
+        assert!(matches!(err, RDNSError::MessageTruncated(3)));
+    }
+
+    #[test]
+    fn read_fewer_questions_than_counted() {
+        let input = vec![
+            0x12, 0x34, 0x01, 0x00, 0x00, 0x02, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 1,
+        ];
+        let err = Message::read(&input).err().unwrap();
+
+        assert!(matches!(err, RDNSError::MessageTruncated(17)));
+    }
+
+    #[test]
+    fn read_with_trailing_bytes() {
+        let input = vec![0x12, 0x34, 0x01, 0x00, 0, 0, 0, 0, 0, 0, 0, 0, 0xFF];
+        let err = Message::read(&input).err().unwrap();
+
+        assert!(matches!(err, RDNSError::MessageFormatError(_)));
+    }
+}
@@ -1,6 +1,9 @@
 use crate::error::RDNSError;
+use crate::wire::{WireReader, WireWriter};
+use std::cmp::Ordering;
 use std::collections::HashSet;
 use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
 use std::iter::Peekable;
 use std::vec::IntoIter;
 
@@ -20,58 +23,407 @@ impl Name {
         Ok(Name(result))
     }
 
+    /// Converts a name which may contain Unicode labels to its ASCII form, applying the UTS #46
+    /// mapping and encoding each non-ASCII label as an A-label (`xn--...`). ASCII names are
+    /// lowercased by the mapping but are otherwise unchanged.
+    pub fn from_unicode(repr: &str) -> Result<Name, RDNSError> {
+        let ascii = idna::Config::default()
+            .to_ascii(repr)
+            .map_err(|e| RDNSError::NameIdnaInvalid(e.to_string()))?;
+
+        Name::try_from(ascii)
+    }
+
+    /// The presentation format of this name with any A-labels decoded to their Unicode form.
+    /// Labels which are not valid A-labels are left as they are.
+    pub fn to_unicode(&self) -> String {
+        format!("{:#}", self)
+    }
+
     pub fn len(&self) -> usize {
         self.0.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Whether this name still needs an origin appended to make it fully qualified
     pub fn is_relative(&self) -> bool {
-        // Null terminated because of ending a '.'
-        self.0.last() == Some(&0)
+        !self.is_absolute()
     }
 
     pub fn raw(&self) -> Vec<u8> {
         self.0.clone()
     }
+
+    /// Whether this name is fully qualified, ending with the root label
+    pub fn is_absolute(&self) -> bool {
+        self.0.last() == Some(&0)
+    }
+
+    /// The labels of this name from left to right, not including the root label
+    pub fn labels(&self) -> Labels<'_> {
+        Labels {
+            name: &self.0,
+            pos: 0,
+        }
+    }
+
+    /// The number of labels in this name, not including the root label
+    pub fn label_count(&self) -> usize {
+        self.labels().count()
+    }
+
+    /// Whether the first label of this name is the wildcard label `*` (RFC 4592)
+    pub fn is_wildcard(&self) -> bool {
+        self.labels().next() == Some(b"*")
+    }
+
+    /// The name with its first label removed. The root has no parent and neither does a relative
+    /// name with a single label.
+    pub fn parent(&self) -> Option<Name> {
+        match self.0.first() {
+            Some(&len) if len != 0 => {
+                let rest = &self.0[len as usize + 1..];
+                if rest.is_empty() {
+                    None
+                } else {
+                    Some(Name(rest.to_vec()))
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether this name is equal to `other` or below it in the tree. Labels are compared without
+    /// regard to case, and an absolute name is never a subdomain of a relative name or vice versa.
+    pub fn is_subdomain_of(&self, other: &Name) -> bool {
+        if self.is_absolute() != other.is_absolute() {
+            return false;
+        }
+
+        let ours = self.labels().collect::<Vec<_>>();
+        let theirs = other.labels().collect::<Vec<_>>();
+
+        ours.len() >= theirs.len()
+            && ours
+                .iter()
+                .rev()
+                .zip(theirs.iter().rev())
+                .all(|(a, b)| a.eq_ignore_ascii_case(b))
+    }
+
+    /// Makes a relative name absolute by appending `origin` to it (RFC 1035 section 5.1). Absolute
+    /// names are returned unchanged.
+    pub fn append(&self, origin: &Name) -> Result<Name, RDNSError> {
+        if self.is_absolute() {
+            return Ok(self.clone());
+        }
+
+        let mut result = self.0.clone();
+        result.extend_from_slice(&origin.0);
+
+        if result.len() > 255 {
+            return Err(RDNSError::NameTooLong(result.len()));
+        }
+
+        Ok(Name(result))
+    }
+
+    /// The part of this name below `origin`, for printing names relative to a zone's origin. The
+    /// origin itself gives the empty name, which is displayed as `@`. Names outside of `origin`
+    /// are returned unchanged.
+    pub fn relativize_to(&self, origin: &Name) -> Name {
+        if !self.is_subdomain_of(origin) {
+            return self.clone();
+        }
+
+        let keep = self.label_count() - origin.label_count();
+        let end = self.labels().take(keep).map(|l| l.len() + 1).sum();
+
+        Name(self.0[..end].to_vec())
+    }
+
+    /// Prepends a single label to this name, as when generating names below an existing one
+    pub fn prepend_label(&self, label: &[u8]) -> Result<Name, RDNSError> {
+        if label.is_empty() {
+            return Err(RDNSError::NameInvalid());
+        }
+        if label.len() > 63 {
+            return Err(RDNSError::NameLabelTooLong(label.len() as u8));
+        }
+
+        let mut result = Vec::with_capacity(self.0.len() + label.len() + 1);
+        result.push(label.len() as u8);
+        result.extend_from_slice(label);
+        result.extend_from_slice(&self.0);
+
+        if result.len() > 255 {
+            return Err(RDNSError::NameTooLong(result.len()));
+        }
+
+        Ok(Name(result))
+    }
+
+    /// A copy of this name with all ASCII letters lowercased, for canonical form (RFC 4034
+    /// section 6.2)
+    pub fn to_lowercase(&self) -> Name {
+        Name(self.0.to_ascii_lowercase())
+    }
+
+    /// Reads a name in wire format, following any compression pointers. Names in a message are
+    /// always fully qualified so the result will end with the root label.
+    pub fn read(reader: &mut WireReader) -> Result<Name, RDNSError> {
+        let mut result = Vec::new();
+
+        // Once a pointer has been followed the rest of the name is read from a second reader, which
+        // leaves the original positioned just after the first pointer. Pointers must refer to an
+        // earlier offset than the last one followed, which guarantees the name cannot loop.
+        let mut pointer_reader: Option<WireReader> = None;
+        let mut lowest_offset = reader.position();
+
+        loop {
+            let current = match pointer_reader.as_mut() {
+                Some(r) => r,
+                None => &mut *reader,
+            };
+
+            let len = current.read_u8()?;
+            match len & 0xC0 {
+                0x00 => {}
+                0xC0 => {
+                    let offset = (((len & 0x3F) as usize) << 8) | current.read_u8()? as usize;
+                    if offset >= lowest_offset {
+                        return Err(RDNSError::NamePointerInvalid(offset));
+                    }
+
+                    lowest_offset = offset;
+                    pointer_reader = Some(current.at(offset));
+                    continue;
+                }
+                _ => {
+                    return Err(RDNSError::MessageFormatError(format!(
+                        "unsupported label type {:#04x}",
+                        len & 0xC0
+                    )));
+                }
+            }
+
+            result.push(len);
+            result.extend_from_slice(current.read_slice(len as usize)?);
+
+            if result.len() > 255 {
+                return Err(RDNSError::NameTooLong(result.len()));
+            }
+
+            if len == 0 {
+                break;
+            }
+        }
+
+        Ok(Name(result))
+    }
+
+    /// Writes this name in wire format, terminated by the root label. Where the message already
+    /// contains a matching suffix the name ends with a pointer to it instead.
+    pub fn write(&self, writer: &mut WireWriter) {
+        self.write_labels(writer, true);
+    }
+
+    /// Writes this name in wire format without compression, for RDATA which other
+    /// implementations may not know how to decompress
+    pub fn write_uncompressed(&self, writer: &mut WireWriter) {
+        self.write_labels(writer, false);
+    }
+
+    fn write_labels(&self, writer: &mut WireWriter, compress: bool) {
+        let wire = self.fully_qualified_wire();
+
+        let mut pos = 0;
+        while wire[pos] != 0 {
+            let suffix = &wire[pos..];
+            if compress {
+                if let Some(offset) = writer.find_name(suffix) {
+                    writer.write_u16(0xC000 | offset);
+                    return;
+                }
+            }
+            writer.remember_name(suffix);
+
+            let len = wire[pos] as usize;
+            writer.write_slice(&wire[pos..pos + len + 1]);
+            pos += len + 1;
+        }
+
+        writer.write_u8(0);
+    }
+
+    /// The labels of this name followed by the root label, whether or not the name is relative
+    fn fully_qualified_wire(&self) -> Vec<u8> {
+        let mut result = Vec::with_capacity(self.0.len() + 1);
+
+        let mut pos = 0;
+        while let Some(&len) = self.0.get(pos) {
+            if len == 0 {
+                break;
+            }
+            result.extend_from_slice(&self.0[pos..pos + len as usize + 1]);
+            pos += len as usize + 1;
+        }
+        result.push(0);
+
+        result
+    }
+}
+
+/// Iterates over the labels of a name, see [`Name::labels`]
+pub struct Labels<'a> {
+    name: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Iterator for Labels<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.name.get(self.pos) {
+            Some(&len) if len != 0 => {
+                let label = &self.name[self.pos + 1..self.pos + len as usize + 1];
+                self.pos += len as usize + 1;
+                Some(label)
+            }
+            _ => None,
+        }
+    }
 }
 
-impl<'a> TryFrom<String> for Name {
+/// Names are compared without regard to the case of ASCII letters (RFC 1035 section 2.3.3). Length
+/// octets are at most 63 so they are never changed by lowercasing.
+impl PartialEq for Name {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_ignore_ascii_case(&other.0)
+    }
+}
+
+impl Eq for Name {}
+
+impl Hash for Name {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for ch in &self.0 {
+            state.write_u8(ch.to_ascii_lowercase());
+        }
+    }
+}
+
+/// The canonical DNS name order from RFC 4034 section 6.1. Names are compared label by label
+/// starting from the root, with each label compared as a lowercase octet string, so that a name
+/// sorts directly before its subdomains. A relative name sorts before the absolute name with the
+/// same labels.
+impl Ord for Name {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let ours = self.labels().collect::<Vec<_>>();
+        let theirs = other.labels().collect::<Vec<_>>();
+
+        for (a, b) in ours.iter().rev().zip(theirs.iter().rev()) {
+            let ordering = a
+                .iter()
+                .map(u8::to_ascii_lowercase)
+                .cmp(b.iter().map(u8::to_ascii_lowercase));
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+
+        ours.len()
+            .cmp(&theirs.len())
+            .then_with(|| self.is_absolute().cmp(&other.is_absolute()))
+    }
+}
+
+impl PartialOrd for Name {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl TryFrom<String> for Name {
     type Error = RDNSError;
 
     fn try_from(repr: String) -> Result<Self, Self::Error> {
-        parser::NameParser::parse_repr(repr).map(|v| Name(v))
+        parser::NameParser::parse_repr(repr).map(Name)
     }
 }
 
+/// The presentation format of the name, with characters which have a special meaning in master
+/// files escaped. The alternate form `{:#}` shows A-labels as Unicode, see [`Name::to_unicode`].
 impl Display for Name {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", <Name as Into<String>>::into(self.clone()))
+        if self.0.len() == 1 && self.0[0] == 0 {
+            return write!(f, ".");
+        }
+        if self.0.is_empty() {
+            return write!(f, "@");
+        }
+
+        for (i, label) in self.labels().enumerate() {
+            if i != 0 {
+                write!(f, ".")?;
+            }
+
+            match decode_a_label(label) {
+                Some(unicode) if f.alternate() => write!(f, "{}", unicode)?,
+                _ if i == 0 && label == b"*" => write!(f, "*")?,
+                _ => write_escaped_label(f, label)?,
+            }
+        }
+
+        if self.is_absolute() {
+            write!(f, ".")?;
+        }
+
+        Ok(())
     }
 }
 
-impl Into<String> for Name {
-    fn into(self) -> String {
-        if self.0.len() == 1 && *self.0.get(0).unwrap() == 0 {
-            return ".".to_string();
+/// Escapes every octet which the name parser would not accept unescaped at its position, so that
+/// the output always parses back to the same label
+fn write_escaped_label(f: &mut Formatter<'_>, label: &[u8]) -> std::fmt::Result {
+    for (i, &ch) in label.iter().enumerate() {
+        match ch {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'_' => write!(f, "{}", ch as char)?,
+            b'-' if i != 0 && i != label.len() - 1 => write!(f, "-")?,
+            0x21..=0x7E => write!(f, "\\{}", ch as char)?,
+            _ => write!(f, "\\{:03}", ch)?,
         }
+    }
 
-        let mut bytes = self.0.clone();
-        let mut pos = 0;
+    Ok(())
+}
 
-        while let Some(&len) = bytes.get(pos) {
-            *bytes.get_mut(pos).unwrap() = b'.';
-            if len == 0 {
-                break;
-            }
-            pos += len as usize + 1;
-        }
+/// The Unicode form of an A-label, or `None` if the label is not a valid A-label
+fn decode_a_label(label: &[u8]) -> Option<String> {
+    if label.len() < 4 || !label[..4].eq_ignore_ascii_case(b"xn--") {
+        return None;
+    }
 
-        String::from_utf8(bytes[1..].to_owned()).unwrap()
+    let label = std::str::from_utf8(label).ok()?;
+    match idna::Config::default().to_unicode(label) {
+        (unicode, Ok(())) => Some(unicode),
+        _ => None,
     }
 }
 
-impl Into<Vec<u8>> for Name {
-    fn into(self) -> Vec<u8> {
-        self.0
+/// The presentation format of the name, the same as its [`Display`] output
+impl From<Name> for String {
+    fn from(name: Name) -> Self {
+        name.to_string()
+    }
+}
+
+impl From<Name> for Vec<u8> {
+    fn from(name: Name) -> Self {
+        name.0
     }
 }
 
@@ -162,16 +514,19 @@ pub mod parser {
             // Placeholder length
             self.result.push(0);
 
-            let mut prev;
-            if self.current_is_letter() {
+            // A wildcard is only allowed as the whole of the first label (RFC 4592)
+            if self.result.len() == 1 && self.repr.peek() == Some(&b'*') {
                 self.pos += 1;
-                self.label_pos += 1;
-                prev = self.repr.next().unwrap();
-                self.result.push(prev as u8);
-            } else {
-                return Err(RDNSError::NameLabelInvalid(self.pos));
+                self.repr.next();
+                self.result[0] = 1;
+                self.result.push(b'*');
+                return Ok(());
             }
 
+            // Underscores are accepted anywhere in a label as in service labels such as _sip and _tcp
+            // (RFC 2782), and labels may begin with a digit (RFC 1123 section 2.1) as in reverse
+            // mapping names. Any other octet can be given escaped, including at the start.
+            let mut prev_is_hyphen = false;
             loop {
                 match self.repr.peek() {
                     Some(b'\\') => {
@@ -204,29 +559,28 @@ pub mod parser {
                             Some(_) => {
                                 self.pos += 1;
                                 self.label_pos += 1;
-                                prev = self.repr.next().unwrap();
+                                let prev = self.repr.next().unwrap();
                                 self.result.push(prev);
                             }
                             None => {
                                 return Err(RDNSError::NameLabelInvalid(self.pos + 1));
                             }
                         }
+                        prev_is_hyphen = false;
                     }
-                    Some(_) => {
-                        if self.current_is_letter_digit_hyphen() {
-                            self.pos += 1;
-                            self.label_pos += 1;
-                            prev = self.repr.next().unwrap();
-                            self.result.push(prev as u8);
-                        } else {
-                            break;
-                        }
+                    Some(&ch) if NameParser::is_label_char(ch, self.label_pos == 0) => {
+                        self.pos += 1;
+                        self.label_pos += 1;
+                        let prev = self.repr.next().unwrap();
+                        self.result.push(prev);
+                        prev_is_hyphen = NameParser::is_hyphen(prev);
                     }
-                    None => break,
+                    _ if self.label_pos == 0 => return Err(RDNSError::NameLabelInvalid(self.pos)),
+                    _ => break,
                 }
             }
 
-            if NameParser::is_hyphen(prev) {
+            if prev_is_hyphen {
                 return Err(RDNSError::NameLabelInvalid(self.pos - 1));
             }
 
@@ -243,43 +597,23 @@ pub mod parser {
             Ok(())
         }
 
-        fn current_is_letter(&mut self) -> bool {
-            if let Some(&ch) = self.repr.peek() {
-                if NameParser::is_letter(ch) {
-                    return true;
-                }
-            }
-
-            false
-        }
-
-        fn current_is_letter_digit_hyphen(&mut self) -> bool {
-            if let Some(&ch) = self.repr.peek() {
-                if NameParser::is_letter(ch)
-                    || NameParser::is_digit(ch)
-                    || NameParser::is_hyphen(ch)
-                {
-                    return true;
-                }
-            }
-
-            false
+        /// A hyphen is not allowed as the first character of a label
+        #[inline]
+        fn is_label_char(ch: u8, first: bool) -> bool {
+            NameParser::is_letter(ch)
+                || NameParser::is_digit(ch)
+                || ch == b'_'
+                || (NameParser::is_hyphen(ch) && !first)
         }
 
         #[inline]
         fn is_letter(ch: u8) -> bool {
-            match ch {
-                b'a'..=b'z' | b'A'..=b'Z' => true,
-                _ => false,
-            }
+            ch.is_ascii_alphabetic()
         }
 
         #[inline]
         fn is_digit(ch: u8) -> bool {
-            match ch {
-                b'0'..=b'9' => true,
-                _ => false,
-            }
+            ch.is_ascii_digit()
         }
 
         #[inline]
@@ -290,10 +624,12 @@ pub mod parser {
 }
 
 #[cfg(test)]
+#[allow(clippy::char_lit_as_u8, clippy::useless_vec)]
 mod tests {
     use crate::error::RDNSError;
     use crate::name::{parser, Name};
     use crate::test;
+    use crate::wire::{WireReader, WireWriter};
     use std::collections::HashSet;
 
     #[test]
@@ -338,6 +674,33 @@ mod tests {
         assert_eq!(expected, name.0);
     }
 
+    #[test]
+    fn service_labels_start_with_underscore() {
+        let test_name = "_sip._tcp.example.com".to_string();
+        let name = Name::try_from(test_name.clone()).unwrap();
+
+        let expected = test::dirty_to_bytes(test_name);
+        assert_eq!(expected, name.0);
+    }
+
+    #[test]
+    fn underscore_allowed_anywhere_in_label() {
+        let test_name = "a_b.c_.example.com".to_string();
+        let name = Name::try_from(test_name.clone()).unwrap();
+
+        let expected = test::dirty_to_bytes(test_name);
+        assert_eq!(expected, name.0);
+    }
+
+    #[test]
+    fn label_may_start_with_digit() {
+        let name = Name::try_from("1.0.0.10.in-addr.arpa.".to_string()).unwrap();
+        assert_eq!("1.0.0.10.in-addr.arpa.", name.to_string());
+
+        let name = Name::try_from("3com.example.com".to_string()).unwrap();
+        assert_eq!(4, name.raw()[0]);
+    }
+
     #[test]
     fn label_must_not_start_with_hyphen() {
         let test_name = "-a.example.com".to_string();
@@ -420,6 +783,330 @@ mod tests {
         assert_eq!(test_name, <Name as Into<String>>::into(name));
     }
 
+    #[test]
+    fn display_absolute_and_relative_names() {
+        assert_eq!(".", Name::root().to_string());
+        assert_eq!(
+            "example.com.",
+            Name::try_from("example.com.".to_string())
+                .unwrap()
+                .to_string()
+        );
+        assert_eq!(
+            "www.example.com",
+            Name::try_from("www.example.com".to_string())
+                .unwrap()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn display_escapes_special_characters() {
+        let name = Name::try_from("ex\\.am\\;ple\\032x\\009.com.".to_string()).unwrap();
+        assert_eq!("ex\\.am\\;ple\\032x\\009.com.", name.to_string());
+    }
+
+    #[test]
+    fn names_equal_ignoring_case() {
+        let lower = Name::try_from("www.example.com.".to_string()).unwrap();
+        let mixed = Name::try_from("WWW.Example.COM.".to_string()).unwrap();
+
+        assert_eq!(lower, mixed);
+        assert_ne!(
+            lower,
+            Name::try_from("www.example.com".to_string()).unwrap()
+        );
+        assert_ne!(
+            lower,
+            Name::try_from("www.example.org.".to_string()).unwrap()
+        );
+    }
+
+    #[test]
+    fn names_hash_ignoring_case() {
+        let mut names = HashSet::new();
+        names.insert(Name::try_from("Example.COM.".to_string()).unwrap());
+
+        assert!(names.contains(&Name::try_from("example.com.".to_string()).unwrap()));
+        assert!(!names.contains(&Name::try_from("example.com".to_string()).unwrap()));
+    }
+
+    #[test]
+    fn canonical_name_order() {
+        // The example from RFC 4034 section 6.1. Labels starting with anything other than a letter
+        // can't be parsed from text yet so the names are built from their labels.
+        let expected: Vec<&[&[u8]]> = vec![
+            &[b"example"],
+            &[b"a", b"example"],
+            &[b"yljkjljk", b"a", b"example"],
+            &[b"Z", b"a", b"example"],
+            &[b"zABC", b"a", b"EXAMPLE"],
+            &[b"z", b"example"],
+            &[b"\x01", b"z", b"example"],
+            &[b"*", b"z", b"example"],
+            &[b"\xC8", b"z", b"example"],
+        ];
+        let expected = expected
+            .iter()
+            .map(|labels| {
+                let mut wire = Vec::new();
+                for label in labels.iter() {
+                    wire.push(label.len() as u8);
+                    wire.extend_from_slice(label);
+                }
+                wire.push(0);
+                Name(wire)
+            })
+            .collect::<Vec<_>>();
+
+        let mut names = expected.iter().rev().cloned().collect::<Vec<_>>();
+        names.sort();
+
+        assert_eq!(expected, names);
+    }
+
+    #[test]
+    fn canonical_order_of_root_and_relative_names() {
+        let root = Name::root();
+        let relative = Name::try_from("example".to_string()).unwrap();
+        let absolute = Name::try_from("example.".to_string()).unwrap();
+
+        assert!(root < relative);
+        assert!(relative < absolute);
+        assert_eq!(
+            std::cmp::Ordering::Equal,
+            absolute.cmp(&Name::try_from("EXAMPLE.".to_string()).unwrap())
+        );
+    }
+
+    #[test]
+    fn canonical_order_agrees_with_equality() {
+        let lower = Name::try_from("a.b".to_string()).unwrap();
+        let upper = Name::try_from("a.B".to_string()).unwrap();
+
+        assert_eq!(lower, upper);
+        assert_eq!(std::cmp::Ordering::Equal, lower.cmp(&upper));
+        assert_eq!(std::cmp::Ordering::Equal, upper.cmp(&lower));
+    }
+
+    #[test]
+    fn labels_of_name() {
+        let name = Name::try_from("www.Example.com.".to_string()).unwrap();
+        assert_eq!(
+            vec![&b"www"[..], &b"Example"[..], &b"com"[..]],
+            name.labels().collect::<Vec<_>>()
+        );
+        assert_eq!(3, name.label_count());
+        assert_eq!(0, Name::root().label_count());
+    }
+
+    #[test]
+    fn absolute_names_end_with_root_label() {
+        assert!(Name::root().is_absolute());
+        assert!(!Name::root().is_relative());
+        assert!(Name::try_from("example.com".to_string())
+            .unwrap()
+            .is_relative());
+        assert!(Name::try_from("example.com.".to_string())
+            .unwrap()
+            .is_absolute());
+        assert!(!Name::try_from("example.com".to_string())
+            .unwrap()
+            .is_absolute());
+    }
+
+    #[test]
+    fn parent_of_name() {
+        let name = Name::try_from("www.example.com.".to_string()).unwrap();
+        let parent = name.parent().unwrap();
+        assert_eq!("example.com.", parent.to_string());
+        assert_eq!(".", parent.parent().unwrap().parent().unwrap().to_string());
+        assert!(Name::root().parent().is_none());
+
+        let relative = Name::try_from("www.example".to_string()).unwrap();
+        assert_eq!("example", relative.parent().unwrap().to_string());
+        assert!(relative.parent().unwrap().parent().is_none());
+    }
+
+    #[test]
+    fn subdomain_of_name() {
+        let name = Name::try_from("www.Example.com.".to_string()).unwrap();
+
+        assert!(name.is_subdomain_of(&Name::try_from("example.COM.".to_string()).unwrap()));
+        assert!(name.is_subdomain_of(&name));
+        assert!(name.is_subdomain_of(&Name::root()));
+        assert!(!name.is_subdomain_of(&Name::try_from("ample.com.".to_string()).unwrap()));
+        assert!(!name.is_subdomain_of(&Name::try_from("example.com".to_string()).unwrap()));
+        assert!(!Name::root().is_subdomain_of(&name));
+    }
+
+    #[test]
+    fn append_origin_to_relative_name() {
+        let origin = Name::try_from("example.com.".to_string()).unwrap();
+
+        let relative = Name::try_from("www".to_string()).unwrap();
+        assert_eq!(
+            "www.example.com.",
+            relative.append(&origin).unwrap().to_string()
+        );
+
+        let absolute = Name::try_from("www.example.org.".to_string()).unwrap();
+        assert_eq!(absolute, absolute.append(&origin).unwrap());
+    }
+
+    #[test]
+    fn append_origin_too_long() {
+        let label = "a".repeat(63);
+        let relative = Name::try_from(format!("{}.{}", label, label)).unwrap();
+        let origin = Name::try_from(format!("{}.{}.", label, label)).unwrap();
+
+        let err = relative.append(&origin).unwrap_err();
+        assert!(matches!(err, RDNSError::NameTooLong(257)));
+    }
+
+    #[test]
+    fn relativize_name_to_origin() {
+        let origin = Name::try_from("example.com.".to_string()).unwrap();
+
+        let name = Name::try_from("www.Example.com.".to_string()).unwrap();
+        let relative = name.relativize_to(&origin);
+        assert_eq!("www", relative.to_string());
+        assert_eq!(name, relative.append(&origin).unwrap());
+
+        let apex = origin.relativize_to(&origin);
+        assert_eq!("@", apex.to_string());
+        assert_eq!(origin, apex.append(&origin).unwrap());
+
+        let outside = Name::try_from("www.example.org.".to_string()).unwrap();
+        assert_eq!(outside, outside.relativize_to(&origin));
+    }
+
+    #[test]
+    fn name_into_string() {
+        let origin = Name::try_from("example.com.".to_string()).unwrap();
+        assert_eq!("example.com.", String::from(origin.clone()));
+        assert_eq!("@", String::from(origin.relativize_to(&origin)));
+        assert_eq!(".", String::from(Name::root()));
+
+        // Labels read from the wire need not be UTF-8
+        let wire = [
+            3, b'a', 0xff, b'.', 7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 0,
+        ];
+        let name = Name::read(&mut WireReader::new(&wire)).unwrap();
+        assert_eq!("a\\255\\..example.", String::from(name));
+    }
+
+    #[test]
+    fn prepend_label_to_name() {
+        let origin = Name::try_from("example.com.".to_string()).unwrap();
+        assert_eq!(
+            "host-1.example.com.",
+            origin.prepend_label(b"host-1").unwrap().to_string()
+        );
+
+        let err = origin.prepend_label(&[b'a'; 64]).unwrap_err();
+        assert!(matches!(err, RDNSError::NameLabelTooLong(64)));
+
+        let err = origin.prepend_label(&[]).unwrap_err();
+        assert!(matches!(err, RDNSError::NameInvalid()));
+    }
+
+    #[test]
+    fn wildcard_names() {
+        let name = Name::try_from("*.example.com.".to_string()).unwrap();
+        assert!(name.is_wildcard());
+        assert_eq!("*.example.com.", name.to_string());
+
+        assert!(!Name::try_from("www.example.com.".to_string())
+            .unwrap()
+            .is_wildcard());
+    }
+
+    #[test]
+    fn wildcard_only_allowed_as_first_label() {
+        let err = Name::try_from("www.*.example.com.".to_string()).unwrap_err();
+        assert!(matches!(err, RDNSError::NameLabelInvalid(4)));
+
+        let err = Name::try_from("*www.example.com.".to_string()).unwrap_err();
+        assert!(matches!(err, RDNSError::NameLabelInvalid(1)));
+    }
+
+    #[test]
+    fn wire_names_round_trip_through_display() {
+        let example = [7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 0];
+        let labels: [&[u8]; 8] = [
+            b"a_b",
+            b"*",
+            b"*a",
+            b"-a",
+            b"a-",
+            b"a b",
+            b"\\.",
+            &[0xff, b'"'],
+        ];
+        for label in labels {
+            // The label both as the first label and below another one
+            for prefix in [&[][..], &[1, b'x']] {
+                let mut wire = prefix.to_vec();
+                wire.push(label.len() as u8);
+                wire.extend_from_slice(label);
+                wire.extend_from_slice(&example);
+
+                let name = Name::read(&mut WireReader::new(&wire)).unwrap();
+                let parsed = Name::try_from(name.to_string()).unwrap();
+                assert_eq!(name, parsed, "{}", name);
+            }
+        }
+
+        let wire = [
+            1, b'x', 1, b'*', 7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 0,
+        ];
+        let name = Name::read(&mut WireReader::new(&wire)).unwrap();
+        assert_eq!("x.\\*.example.", name.to_string());
+    }
+
+    #[test]
+    fn lowercase_name() {
+        let name = Name::try_from("WWW.Example.com.".to_string()).unwrap();
+        assert_eq!("www.example.com.", name.to_lowercase().to_string());
+    }
+
+    #[test]
+    fn unicode_name_to_a_labels() {
+        let name = Name::from_unicode("Bücher.example.").unwrap();
+        assert_eq!("xn--bcher-kva.example.", name.to_string());
+
+        let name = Name::from_unicode("www.例え.jp").unwrap();
+        assert_eq!("www.xn--r8jz45g.jp", name.to_string());
+        assert!(name.is_relative());
+    }
+
+    #[test]
+    fn ascii_name_from_unicode_is_unchanged() {
+        let name = Name::from_unicode("_sip._tcp.example.com.").unwrap();
+        assert_eq!("_sip._tcp.example.com.", name.to_string());
+    }
+
+    #[test]
+    fn invalid_unicode_name() {
+        let err = Name::from_unicode("xn--a.example.").unwrap_err();
+        assert!(matches!(err, RDNSError::NameIdnaInvalid(_)));
+    }
+
+    #[test]
+    fn a_labels_to_unicode() {
+        let name = Name::try_from("www.XN--bcher-kva.example.".to_string()).unwrap();
+        assert_eq!("www.bücher.example.", name.to_unicode());
+        assert_eq!("www.bücher.example.", format!("{:#}", name));
+        assert_eq!("www.XN--bcher-kva.example.", name.to_string());
+    }
+
+    #[test]
+    fn invalid_a_labels_are_not_decoded() {
+        let name = Name::try_from("xn--a.example.".to_string()).unwrap();
+        assert_eq!("xn--a.example.", name.to_unicode());
+    }
+
     #[test]
     fn parse_with_stop_pattern_for_example_dot_com_absolute() {
         let test_name = "example.com. ".to_string();
@@ -500,8 +1187,155 @@ mod tests {
         );
 
         assert_eq!(
-            "ex.mple.com".to_string(),
+            "ex\\.mple.com".to_string(),
             <Name as Into<String>>::into(name)
         );
     }
+
+    #[test]
+    fn read_wire_format_name() {
+        let input = test::dirty_to_bytes("example.com.".to_string());
+        let name = Name::read(&mut WireReader::new(&input)).unwrap();
+
+        assert_eq!(input, name.0);
+    }
+
+    #[test]
+    fn read_wire_format_root_name() {
+        let name = Name::read(&mut WireReader::new(&[0])).unwrap();
+
+        assert_eq!(vec![0], name.0);
+    }
+
+    #[test]
+    fn read_wire_format_name_missing_root_label() {
+        let input = test::dirty_to_bytes("example.com".to_string());
+        let err = Name::read(&mut WireReader::new(&input)).unwrap_err();
+
+        assert!(matches!(err, RDNSError::MessageTruncated(12)));
+    }
+
+    #[test]
+    fn read_wire_format_name_with_reserved_label_type() {
+        let err = Name::read(&mut WireReader::new(&[0x40, 0])).unwrap_err();
+
+        assert!(matches!(err, RDNSError::MessageFormatError(_)));
+    }
+
+    #[test]
+    fn write_relative_name_as_fully_qualified() {
+        let name = Name::try_from("example.com".to_string()).unwrap();
+        let mut writer = WireWriter::new();
+        name.write(&mut writer);
+
+        assert_eq!(
+            test::dirty_to_bytes("example.com.".to_string()),
+            writer.into_bytes()
+        );
+    }
+
+    #[test]
+    fn read_compressed_name() {
+        let mut input = test::dirty_to_bytes("example.com.".to_string());
+        input.extend_from_slice(&[3, b'w', b'w', b'w', 0xC0, 0]);
+
+        let mut reader = WireReader::new(&input);
+        Name::read(&mut reader).unwrap();
+        let name = Name::read(&mut reader).unwrap();
+
+        assert_eq!(test::dirty_to_bytes("www.example.com.".to_string()), name.0);
+        assert_eq!(0, reader.remaining());
+    }
+
+    #[test]
+    fn read_compressed_name_through_multiple_pointers() {
+        let mut input = test::dirty_to_bytes("com.".to_string());
+        input.extend_from_slice(&[7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 0xC0, 0]);
+        input.extend_from_slice(&[3, b'w', b'w', b'w', 0xC0, 5]);
+
+        let mut reader = WireReader::new(&input);
+        reader.read_slice(15).unwrap();
+        let name = Name::read(&mut reader).unwrap();
+
+        assert_eq!(test::dirty_to_bytes("www.example.com.".to_string()), name.0);
+    }
+
+    #[test]
+    fn read_compressed_name_pointing_to_itself() {
+        let input = [0xC0, 0];
+        let err = Name::read(&mut WireReader::new(&input)).unwrap_err();
+
+        assert!(matches!(err, RDNSError::NamePointerInvalid(0)));
+    }
+
+    #[test]
+    fn read_compressed_name_pointing_forwards() {
+        let input = [0xC0, 2, 0];
+        let err = Name::read(&mut WireReader::new(&input)).unwrap_err();
+
+        assert!(matches!(err, RDNSError::NamePointerInvalid(2)));
+    }
+
+    #[test]
+    fn read_compressed_name_with_pointer_loop() {
+        // The second name points at the first, which points back at the second
+        let input = [3, b'c', b'o', b'm', 0xC0, 6, 3, b'w', b'w', b'w', 0xC0, 0];
+        let mut reader = WireReader::new(&input);
+        reader.read_slice(6).unwrap();
+        let err = Name::read(&mut reader).unwrap_err();
+
+        assert!(matches!(err, RDNSError::NamePointerInvalid(6)));
+    }
+
+    #[test]
+    fn read_compressed_name_with_truncated_pointer() {
+        let err = Name::read(&mut WireReader::new(&[0xC0])).unwrap_err();
+
+        assert!(matches!(err, RDNSError::MessageTruncated(1)));
+    }
+
+    #[test]
+    fn write_compressed_suffix() {
+        let mut writer = WireWriter::new();
+        Name::try_from("example.com.".to_string())
+            .unwrap()
+            .write(&mut writer);
+        Name::try_from("www.Example.com.".to_string())
+            .unwrap()
+            .write(&mut writer);
+
+        let mut expected = test::dirty_to_bytes("example.com.".to_string());
+        expected.extend_from_slice(&[3, b'w', b'w', b'w', 0xC0, 0]);
+        assert_eq!(expected, writer.into_bytes());
+    }
+
+    #[test]
+    fn write_uncompressed_repeated_name() {
+        let name = Name::try_from("example.com.".to_string()).unwrap();
+        let mut writer = WireWriter::new();
+        name.write(&mut writer);
+        name.write_uncompressed(&mut writer);
+
+        let mut expected = test::dirty_to_bytes("example.com.".to_string());
+        expected.extend(test::dirty_to_bytes("example.com.".to_string()));
+        assert_eq!(expected, writer.into_bytes());
+    }
+
+    #[test]
+    fn round_trip_compressed_names() {
+        let names = ["example.com.", "mail.example.com.", "www.example.org.", "."];
+        let mut writer = WireWriter::new();
+        for name in names {
+            Name::try_from(name.to_string()).unwrap().write(&mut writer);
+        }
+
+        let bytes = writer.into_bytes();
+        let mut reader = WireReader::new(&bytes);
+        for name in names {
+            assert_eq!(
+                name.to_string(),
+                <Name as Into<String>>::into(Name::read(&mut reader).unwrap())
+            );
+        }
+    }
 }
@@ -1,35 +1,97 @@
 use crate::error::RDNSError;
 use crate::name::Name;
-use std::fmt::Debug;
-use std::net::Ipv4Addr;
+use crate::wire::{WireReader, WireWriter};
+use crate::{ttl, RRType};
+use std::fmt::{Debug, Display, Formatter};
+use std::net::{Ipv4Addr, Ipv6Addr};
 
-pub trait ResourceData: Debug {
+/// The data of a resource record, which is displayed in its master file presentation format
+pub trait ResourceData: Debug + Display {
     fn serialise(&self) -> Vec<u8>;
+
+    /// Writes the resource data into a message. Types defined in RFC 1035 override this to
+    /// compress the domain names they contain, which is not permitted for other types.
+    fn write(&self, writer: &mut WireWriter) {
+        writer.write_slice(&self.serialise());
+    }
 }
 
-#[derive(Debug)]
-struct RawResourceData<'a>(&'a [u8]);
+/// Resource data which is kept as uninterpreted octets
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawResourceData(pub Vec<u8>);
 
-impl<'a> RawResourceData<'a> {
-    fn read(source: &'a [u8]) -> Result<Self, RDNSError> {
-        Ok(RawResourceData(source))
+impl RawResourceData {
+    pub fn read(reader: &mut WireReader, rd_length: u16) -> Result<Self, RDNSError> {
+        Ok(RawResourceData(
+            reader.read_slice(rd_length as usize)?.to_owned(),
+        ))
     }
 }
 
-impl<'a> ResourceData for RawResourceData<'a> {
+impl ResourceData for RawResourceData {
     fn serialise(&self) -> Vec<u8> {
         self.0.to_owned()
     }
 }
 
-#[derive(Debug)]
+/// The data of a record whose TYPE is not known by this implementation. The TYPE code is private
+/// so that it can never be one of the known types, see [`RData::unknown`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownResourceData {
+    rr_type: u16,
+    data: RawResourceData,
+}
+
+impl UnknownResourceData {
+    pub fn rr_type(&self) -> u16 {
+        self.rr_type
+    }
+
+    pub fn data(&self) -> &RawResourceData {
+        &self.data
+    }
+}
+
+impl ResourceData for UnknownResourceData {
+    fn serialise(&self) -> Vec<u8> {
+        self.data.serialise()
+    }
+}
+
+impl Display for UnknownResourceData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.data, f)
+    }
+}
+
+/// The generic `\# <length> <hex>` presentation format from RFC 3597
+impl Display for RawResourceData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\\# {}", self.0.len())?;
+        if !self.0.is_empty() {
+            write!(f, " ")?;
+            for octet in &self.0 {
+                write!(f, "{:02X}", octet)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct AliasResourceData(pub Ipv4Addr);
 
 impl AliasResourceData {
-    fn read(source: &[u8]) -> Result<Self, RDNSError> {
-        Ok(AliasResourceData(Ipv4Addr::new(
-            source[0], source[1], source[2], source[3],
-        )))
+    pub fn read(reader: &mut WireReader, rd_length: u16) -> Result<Self, RDNSError> {
+        if rd_length != 4 {
+            return Err(RDNSError::MessageFormatError(format!(
+                "an A record must have 4 bytes of data, found {}",
+                rd_length
+            )));
+        }
+
+        Ok(AliasResourceData(Ipv4Addr::from(reader.read_u32()?)))
     }
 }
 
@@ -39,14 +101,49 @@ impl ResourceData for AliasResourceData {
     }
 }
 
-#[derive(Debug)]
+impl Display for AliasResourceData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// An IPv6 host address, the data of an AAAA record
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ipv6AddressResourceData(pub Ipv6Addr);
+
+impl Ipv6AddressResourceData {
+    pub fn read(reader: &mut WireReader, rd_length: u16) -> Result<Self, RDNSError> {
+        if rd_length != 16 {
+            return Err(RDNSError::MessageFormatError(format!(
+                "an AAAA record must have 16 bytes of data, found {}",
+                rd_length
+            )));
+        }
+
+        let mut octets = [0u8; 16];
+        octets.copy_from_slice(reader.read_slice(16)?);
+        Ok(Ipv6AddressResourceData(Ipv6Addr::from(octets)))
+    }
+}
+
+impl ResourceData for Ipv6AddressResourceData {
+    fn serialise(&self) -> Vec<u8> {
+        self.0.octets().to_vec()
+    }
+}
+
+impl Display for Ipv6AddressResourceData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct NameServerResourceData(pub Name);
 
 impl NameServerResourceData {
-    fn read(source: &[u8]) -> Result<Self, RDNSError> {
-        let name_str = String::from_utf8(source.to_owned())?;
-        let name = Name::try_from(name_str)?;
-        Ok(NameServerResourceData(name))
+    pub fn read(reader: &mut WireReader) -> Result<Self, RDNSError> {
+        Ok(NameServerResourceData(Name::read(reader)?))
     }
 }
 
@@ -54,16 +151,24 @@ impl ResourceData for NameServerResourceData {
     fn serialise(&self) -> Vec<u8> {
         self.0.clone().into()
     }
+
+    fn write(&self, writer: &mut WireWriter) {
+        self.0.write(writer);
+    }
+}
+
+impl Display for NameServerResourceData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct CNameResourceData(pub Name);
 
 impl CNameResourceData {
-    fn read(source: &[u8]) -> Result<Self, RDNSError> {
-        let name_str = String::from_utf8(source.to_owned())?;
-        let name = Name::try_from(name_str)?;
-        Ok(CNameResourceData(name))
+    pub fn read(reader: &mut WireReader) -> Result<Self, RDNSError> {
+        Ok(CNameResourceData(Name::read(reader)?))
     }
 }
 
@@ -71,9 +176,149 @@ impl ResourceData for CNameResourceData {
     fn serialise(&self) -> Vec<u8> {
         self.0.clone().into()
     }
+
+    fn write(&self, writer: &mut WireWriter) {
+        self.0.write(writer);
+    }
+}
+
+impl Display for CNameResourceData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A host which has a mail agent for the domain, OBSOLETE use MX
+#[derive(Debug, Clone, PartialEq)]
+pub struct MailDestinationResourceData(pub Name);
+
+impl MailDestinationResourceData {
+    pub fn read(reader: &mut WireReader) -> Result<Self, RDNSError> {
+        Ok(MailDestinationResourceData(Name::read(reader)?))
+    }
+}
+
+impl ResourceData for MailDestinationResourceData {
+    fn serialise(&self) -> Vec<u8> {
+        self.0.clone().into()
+    }
+
+    fn write(&self, writer: &mut WireWriter) {
+        self.0.write(writer);
+    }
+}
+
+impl Display for MailDestinationResourceData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A host which has a mail agent which will forward mail for the domain, OBSOLETE use MX
+#[derive(Debug, Clone, PartialEq)]
+pub struct MailForwarderResourceData(pub Name);
+
+impl MailForwarderResourceData {
+    pub fn read(reader: &mut WireReader) -> Result<Self, RDNSError> {
+        Ok(MailForwarderResourceData(Name::read(reader)?))
+    }
+}
+
+impl ResourceData for MailForwarderResourceData {
+    fn serialise(&self) -> Vec<u8> {
+        self.0.clone().into()
+    }
+
+    fn write(&self, writer: &mut WireWriter) {
+        self.0.write(writer);
+    }
+}
+
+impl Display for MailForwarderResourceData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A host which has the specified mailbox _EXPERIMENTAL_
+#[derive(Debug, Clone, PartialEq)]
+pub struct MailboxResourceData(pub Name);
+
+impl MailboxResourceData {
+    pub fn read(reader: &mut WireReader) -> Result<Self, RDNSError> {
+        Ok(MailboxResourceData(Name::read(reader)?))
+    }
+}
+
+impl ResourceData for MailboxResourceData {
+    fn serialise(&self) -> Vec<u8> {
+        self.0.clone().into()
+    }
+
+    fn write(&self, writer: &mut WireWriter) {
+        self.0.write(writer);
+    }
+}
+
+impl Display for MailboxResourceData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A mailbox which is a member of the mail group _EXPERIMENTAL_
+#[derive(Debug, Clone, PartialEq)]
+pub struct MailGroupResourceData(pub Name);
+
+impl MailGroupResourceData {
+    pub fn read(reader: &mut WireReader) -> Result<Self, RDNSError> {
+        Ok(MailGroupResourceData(Name::read(reader)?))
+    }
+}
+
+impl ResourceData for MailGroupResourceData {
+    fn serialise(&self) -> Vec<u8> {
+        self.0.clone().into()
+    }
+
+    fn write(&self, writer: &mut WireWriter) {
+        self.0.write(writer);
+    }
+}
+
+impl Display for MailGroupResourceData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A mailbox which is the proper rename of the specified mailbox _EXPERIMENTAL_
+#[derive(Debug, Clone, PartialEq)]
+pub struct MailRenameResourceData(pub Name);
+
+impl MailRenameResourceData {
+    pub fn read(reader: &mut WireReader) -> Result<Self, RDNSError> {
+        Ok(MailRenameResourceData(Name::read(reader)?))
+    }
+}
+
+impl ResourceData for MailRenameResourceData {
+    fn serialise(&self) -> Vec<u8> {
+        self.0.clone().into()
+    }
+
+    fn write(&self, writer: &mut WireWriter) {
+        self.0.write(writer);
+    }
+}
+
+impl Display for MailRenameResourceData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct SOAResourceData {
     /// The name of the primary name server hosting the zone described by this SOA. Known as
     /// the MNAME in RFC 1035
@@ -92,6 +337,34 @@ pub struct SOAResourceData {
     pub minimum: u32,
 }
 
+impl SOAResourceData {
+    /// The presentation format with the timers written with units, such as `1h30m`
+    pub fn display_with_units(&self) -> String {
+        format!(
+            "{} {} {} {} {} {} {}",
+            self.primary_name,
+            self.responsible_name,
+            self.serial,
+            ttl::format_signed(self.refresh),
+            ttl::format_signed(self.retry),
+            ttl::format_signed(self.expire),
+            ttl::format(self.minimum)
+        )
+    }
+
+    pub fn read(reader: &mut WireReader) -> Result<Self, RDNSError> {
+        Ok(SOAResourceData {
+            primary_name: Name::read(reader)?,
+            responsible_name: Name::read(reader)?,
+            serial: reader.read_u32()?,
+            refresh: reader.read_i32()?,
+            retry: reader.read_i32()?,
+            expire: reader.read_i32()?,
+            minimum: reader.read_u32()?,
+        })
+    }
+}
+
 impl ResourceData for SOAResourceData {
     fn serialise(&self) -> Vec<u8> {
         let mut result =
@@ -106,32 +379,242 @@ impl ResourceData for SOAResourceData {
 
         result
     }
+
+    fn write(&self, writer: &mut WireWriter) {
+        self.primary_name.write(writer);
+        self.responsible_name.write(writer);
+        writer.write_u32(self.serial);
+        writer.write_i32(self.refresh);
+        writer.write_i32(self.retry);
+        writer.write_i32(self.expire);
+        writer.write_u32(self.minimum);
+    }
 }
 
-#[derive(Debug)]
-struct HInfoResourceData(String);
+impl Display for SOAResourceData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {} {}",
+            self.primary_name,
+            self.responsible_name,
+            self.serial,
+            self.refresh,
+            self.retry,
+            self.expire,
+            self.minimum
+        )
+    }
+}
+
+/// Host information, the CPU and operating system of a host. Each is a single
+/// `<character-string>`, so is at most 255 bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HInfoResourceData {
+    cpu: Vec<u8>,
+    os: Vec<u8>,
+}
 
 impl HInfoResourceData {
-    fn read(source: &[u8]) -> Result<Self, RDNSError> {
-        let name_str = String::from_utf8(source.to_owned())?;
-        Ok(HInfoResourceData(name_str))
+    pub fn new(cpu: Vec<u8>, os: Vec<u8>) -> Result<Self, RDNSError> {
+        for value in [&cpu, &os] {
+            if value.len() > 255 {
+                return Err(RDNSError::CharacterStringTooLong(value.len()));
+            }
+        }
+
+        Ok(HInfoResourceData { cpu, os })
+    }
+
+    pub fn cpu(&self) -> &[u8] {
+        &self.cpu
+    }
+
+    pub fn os(&self) -> &[u8] {
+        &self.os
+    }
+
+    pub fn read(reader: &mut WireReader) -> Result<Self, RDNSError> {
+        Ok(HInfoResourceData {
+            cpu: read_character_string(reader)?,
+            os: read_character_string(reader)?,
+        })
     }
 }
 
 impl ResourceData for HInfoResourceData {
     fn serialise(&self) -> Vec<u8> {
-        self.0.clone().into_bytes()
+        let mut result = Vec::with_capacity(2 + self.cpu.len() + self.os.len());
+        write_character_string(&mut result, &self.cpu);
+        write_character_string(&mut result, &self.os);
+
+        result
+    }
+}
+
+impl Display for HInfoResourceData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            CharacterString(&self.cpu),
+            CharacterString(&self.os)
+        )
+    }
+}
+
+/// Mailbox or mail list information
+#[derive(Debug, Clone, PartialEq)]
+pub struct MailInfoResourceData {
+    /// The mailbox responsible for the mailing list or mailbox. Known as RMAILBX in RFC 1035.
+    pub responsible_mailbox: Name,
+    /// The mailbox which should receive error messages. Known as EMAILBX in RFC 1035.
+    pub error_mailbox: Name,
+}
+
+impl MailInfoResourceData {
+    pub fn read(reader: &mut WireReader) -> Result<Self, RDNSError> {
+        Ok(MailInfoResourceData {
+            responsible_mailbox: Name::read(reader)?,
+            error_mailbox: Name::read(reader)?,
+        })
     }
 }
 
-#[derive(Debug)]
+impl ResourceData for MailInfoResourceData {
+    fn serialise(&self) -> Vec<u8> {
+        let mut result =
+            Vec::with_capacity(self.responsible_mailbox.len() + self.error_mailbox.len());
+        result.append(&mut self.responsible_mailbox.clone().into());
+        result.append(&mut self.error_mailbox.clone().into());
+
+        result
+    }
+
+    fn write(&self, writer: &mut WireWriter) {
+        self.responsible_mailbox.write(writer);
+        self.error_mailbox.write(writer);
+    }
+}
+
+impl Display for MailInfoResourceData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.responsible_mailbox, self.error_mailbox)
+    }
+}
+
+/// The services supported by a particular protocol on a host
+#[derive(Debug, Clone, PartialEq)]
+pub struct WellKnownServiceResourceData {
+    pub address: Ipv4Addr,
+    /// An IP protocol number, such as 6 for TCP or 17 for UDP
+    pub protocol: u8,
+    /// One bit per port, with the most significant bit of the first octet representing port 0
+    pub bitmap: Vec<u8>,
+}
+
+impl WellKnownServiceResourceData {
+    pub fn read(reader: &mut WireReader, rd_length: u16) -> Result<Self, RDNSError> {
+        if rd_length < 5 {
+            return Err(RDNSError::MessageFormatError(format!(
+                "a WKS record must have at least 5 bytes of data, found {}",
+                rd_length
+            )));
+        }
+
+        Ok(WellKnownServiceResourceData {
+            address: Ipv4Addr::from(reader.read_u32()?),
+            protocol: reader.read_u8()?,
+            bitmap: reader.read_slice(rd_length as usize - 5)?.to_owned(),
+        })
+    }
+
+    /// Creates the data for a set of supported ports
+    pub fn from_ports(address: Ipv4Addr, protocol: u8, ports: &[u16]) -> Self {
+        let mut bitmap = Vec::new();
+        for &port in ports {
+            let index = port as usize / 8;
+            if bitmap.len() <= index {
+                bitmap.resize(index + 1, 0);
+            }
+            bitmap[index] |= 0x80 >> (port % 8);
+        }
+
+        WellKnownServiceResourceData {
+            address,
+            protocol,
+            bitmap,
+        }
+    }
+
+    /// The ports which are set in the bitmap, in ascending order
+    pub fn ports(&self) -> Vec<u16> {
+        let mut ports = Vec::new();
+        for (index, octet) in self.bitmap.iter().enumerate() {
+            for bit in 0..8 {
+                if octet & (0x80 >> bit) != 0 {
+                    ports.push((index * 8 + bit) as u16);
+                }
+            }
+        }
+
+        ports
+    }
+}
+
+impl ResourceData for WellKnownServiceResourceData {
+    fn serialise(&self) -> Vec<u8> {
+        let mut result = Vec::with_capacity(5 + self.bitmap.len());
+        result.extend_from_slice(&self.address.octets());
+        result.push(self.protocol);
+        result.extend_from_slice(&self.bitmap);
+
+        result
+    }
+}
+
+impl Display for WellKnownServiceResourceData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.address, self.protocol)?;
+        for port in self.ports() {
+            write!(f, " {}", port)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Anything at all, up to 65535 octets _EXPERIMENTAL_
+#[derive(Debug, Clone, PartialEq)]
+pub struct NullResourceData(pub Vec<u8>);
+
+impl NullResourceData {
+    pub fn read(reader: &mut WireReader, rd_length: u16) -> Result<Self, RDNSError> {
+        Ok(NullResourceData(
+            reader.read_slice(rd_length as usize)?.to_owned(),
+        ))
+    }
+}
+
+impl ResourceData for NullResourceData {
+    fn serialise(&self) -> Vec<u8> {
+        self.0.clone()
+    }
+}
+
+impl Display for NullResourceData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // NULL records have no presentation format of their own
+        write!(f, "{}", RawResourceData(self.0.clone()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct PointerResourceData(pub Name);
 
 impl PointerResourceData {
-    fn read(source: &[u8]) -> Result<Self, RDNSError> {
-        let name_str = String::from_utf8(source.to_owned())?;
-        let name = Name::try_from(name_str)?;
-        Ok(PointerResourceData(name))
+    pub fn read(reader: &mut WireReader) -> Result<Self, RDNSError> {
+        Ok(PointerResourceData(Name::read(reader)?))
     }
 }
 
@@ -139,19 +622,29 @@ impl ResourceData for PointerResourceData {
     fn serialise(&self) -> Vec<u8> {
         self.0.clone().into()
     }
+
+    fn write(&self, writer: &mut WireWriter) {
+        self.0.write(writer);
+    }
 }
 
-#[derive(Debug)]
+impl Display for PointerResourceData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct MailExchangeResourceData {
     pub preference: u16,
     pub exchange: Name,
 }
 
 impl MailExchangeResourceData {
-    fn read(priority: u16, exchange: &[u8]) -> Result<Self, RDNSError> {
+    pub fn read(reader: &mut WireReader) -> Result<Self, RDNSError> {
         Ok(MailExchangeResourceData {
-            preference: priority,
-            exchange: Name::try_from(String::from_utf8(exchange.to_owned())?)?,
+            preference: reader.read_u16()?,
+            exchange: Name::read(reader)?,
         })
     }
 }
@@ -165,46 +658,819 @@ impl ResourceData for MailExchangeResourceData {
 
         result
     }
+
+    fn write(&self, writer: &mut WireWriter) {
+        writer.write_u16(self.preference);
+        self.exchange.write(writer);
+    }
+}
+
+impl Display for MailExchangeResourceData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.preference, self.exchange)
+    }
+}
+
+/// Descriptive text, the data of a TXT record. Each entry is a `<character-string>` of at most
+/// 255 bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextResourceData(pub Vec<Vec<u8>>);
+
+impl TextResourceData {
+    pub fn read(reader: &mut WireReader, rd_length: u16) -> Result<Self, RDNSError> {
+        if rd_length == 0 {
+            return Err(RDNSError::MessageFormatError(
+                "a TXT record must contain at least one character-string".to_string(),
+            ));
+        }
+
+        let end = reader.position() + rd_length as usize;
+        let mut strings = Vec::new();
+        while reader.position() < end {
+            strings.push(read_character_string(reader)?);
+        }
+
+        Ok(TextResourceData(strings))
+    }
+}
+
+impl ResourceData for TextResourceData {
+    fn serialise(&self) -> Vec<u8> {
+        let mut result = Vec::new();
+        for string in &self.0 {
+            for part in split_character_string(string) {
+                write_character_string(&mut result, &part);
+            }
+        }
+
+        result
+    }
+}
+
+impl Display for TextResourceData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, string) in self.0.iter().enumerate() {
+            if i != 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", CharacterString(string))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Displays a `<character-string>` in quotes, escaping quotes, backslashes and any bytes which
+/// are not printable
+struct CharacterString<'a>(&'a [u8]);
+
+impl<'a> Display for CharacterString<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\"")?;
+        for &ch in self.0 {
+            match ch {
+                b'"' | b'\\' => write!(f, "\\{}", ch as char)?,
+                0x20..=0x7E => write!(f, "{}", ch as char)?,
+                _ => write!(f, "\\{:03}", ch)?,
+            }
+        }
+        write!(f, "\"")
+    }
+}
+
+/// Writes a length prefixed `<character-string>`. Callers keep values to 255 bytes, by checking
+/// them when the data is built or by splitting them with [`split_character_string`].
+fn write_character_string(result: &mut Vec<u8>, value: &[u8]) {
+    let len = u8::try_from(value.len()).expect("a character-string is at most 255 bytes");
+    result.push(len);
+    result.extend_from_slice(value);
+}
+
+/// Reads a length prefixed `<character-string>`
+pub fn read_character_string(reader: &mut WireReader) -> Result<Vec<u8>, RDNSError> {
+    let len = reader.read_u8()?;
+    Ok(reader.read_slice(len as usize)?.to_owned())
+}
+
+/// Splits a value into as many `<character-string>`s as are needed to hold it, since each one is
+/// limited to 255 bytes. An empty value is kept as a single empty string.
+pub fn split_character_string(value: &[u8]) -> Vec<Vec<u8>> {
+    if value.is_empty() {
+        return vec![Vec::new()];
+    }
+
+    value.chunks(255).map(|chunk| chunk.to_vec()).collect()
+}
+
+/// The location of the server(s) for a service (RFC 2782)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServiceResourceData {
+    /// The priority of this target host, clients use the lowest priority they can reach
+    pub priority: u16,
+    /// The relative weight for entries with the same priority, used for load balancing
+    pub weight: u16,
+    /// The port on the target host of this service
+    pub port: u16,
+    /// The domain name of the target host
+    pub target: Name,
+}
+
+impl ServiceResourceData {
+    pub fn read(reader: &mut WireReader) -> Result<Self, RDNSError> {
+        Ok(ServiceResourceData {
+            priority: reader.read_u16()?,
+            weight: reader.read_u16()?,
+            port: reader.read_u16()?,
+            target: Name::read(reader)?,
+        })
+    }
+}
+
+impl ResourceData for ServiceResourceData {
+    fn serialise(&self) -> Vec<u8> {
+        let mut result = Vec::with_capacity(6 + self.target.len());
+        result.extend_from_slice(&self.priority.to_be_bytes());
+        result.extend_from_slice(&self.weight.to_be_bytes());
+        result.extend_from_slice(&self.port.to_be_bytes());
+        result.extend(<Name as Into<Vec<u8>>>::into(self.target.clone()).as_slice());
+
+        result
+    }
+
+    fn write(&self, writer: &mut WireWriter) {
+        // RFC 2782 does not allow the target to be compressed
+        writer.write_u16(self.priority);
+        writer.write_u16(self.weight);
+        writer.write_u16(self.port);
+        self.target.write_uncompressed(writer);
+    }
+}
+
+impl Display for ServiceResourceData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.priority, self.weight, self.port, self.target
+        )
+    }
+}
+
+/// The data of a resource record, with a variant for each TYPE this implementation understands so
+/// that the data can be matched on. The TYPE of a record is decided by the variant of its data.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RData {
+    /// A host address
+    A(AliasResourceData),
+    /// An authoritative name server
+    NS(NameServerResourceData),
+    /// A mail destination, obsolete
+    MD(MailDestinationResourceData),
+    /// A mail forwarder, obsolete
+    MF(MailForwarderResourceData),
+    /// The canonical name for an alias
+    CNAME(CNameResourceData),
+    /// The start of a zone of authority
+    SOA(SOAResourceData),
+    /// A mailbox domain name
+    MB(MailboxResourceData),
+    /// A mail group member
+    MG(MailGroupResourceData),
+    /// A mail rename domain name
+    MR(MailRenameResourceData),
+    /// Any data of up to 65535 octets
+    NULL(NullResourceData),
+    /// A well known service description
+    WKS(WellKnownServiceResourceData),
+    /// A domain name pointer
+    PTR(PointerResourceData),
+    /// Host information
+    HINFO(HInfoResourceData),
+    /// Mailbox or mail list information
+    MINFO(MailInfoResourceData),
+    /// A mail exchange
+    MX(MailExchangeResourceData),
+    /// Text strings
+    TXT(TextResourceData),
+    /// An IPv6 host address
+    AAAA(Ipv6AddressResourceData),
+    /// A service locator
+    SRV(ServiceResourceData),
+    /// Data of a TYPE which is not known by this implementation, kept as raw octets
+    Unknown(UnknownResourceData),
+}
+
+impl RData {
+    /// Data given as raw octets for a TYPE code. Codes of known types are decoded into their own
+    /// variant, so the data must then be valid for that type.
+    pub fn unknown(rr_type: u16, data: RawResourceData) -> Result<RData, RDNSError> {
+        let rd_length = u16::try_from(data.0.len()).map_err(|_| {
+            RDNSError::MessageFormatError(format!(
+                "the record data is {} bytes, more than the limit of {}",
+                data.0.len(),
+                u16::MAX
+            ))
+        })?;
+
+        read_resource_data(
+            &RRType::from_value(rr_type),
+            &mut WireReader::new(&data.0),
+            rd_length,
+        )
+    }
+
+    /// The TYPE of a resource record holding this data
+    pub fn rr_type(&self) -> RRType<u16> {
+        match self {
+            RData::A(_) => RRType::A,
+            RData::NS(_) => RRType::NS,
+            RData::MD(_) => RRType::MD,
+            RData::MF(_) => RRType::MF,
+            RData::CNAME(_) => RRType::CNAME,
+            RData::SOA(_) => RRType::SOA,
+            RData::MB(_) => RRType::MB,
+            RData::MG(_) => RRType::MG,
+            RData::MR(_) => RRType::MR,
+            RData::NULL(_) => RRType::NULL,
+            RData::WKS(_) => RRType::WKS,
+            RData::PTR(_) => RRType::PTR,
+            RData::HINFO(_) => RRType::HINFO,
+            RData::MINFO(_) => RRType::MINFO,
+            RData::MX(_) => RRType::MX,
+            RData::TXT(_) => RRType::TXT,
+            RData::AAAA(_) => RRType::AAAA,
+            RData::SRV(_) => RRType::SRV,
+            RData::Unknown(data) => RRType::UNKNOWN(data.rr_type),
+        }
+    }
+
+    fn inner(&self) -> &dyn ResourceData {
+        match self {
+            RData::A(data) => data,
+            RData::NS(data) => data,
+            RData::MD(data) => data,
+            RData::MF(data) => data,
+            RData::CNAME(data) => data,
+            RData::SOA(data) => data,
+            RData::MB(data) => data,
+            RData::MG(data) => data,
+            RData::MR(data) => data,
+            RData::NULL(data) => data,
+            RData::WKS(data) => data,
+            RData::PTR(data) => data,
+            RData::HINFO(data) => data,
+            RData::MINFO(data) => data,
+            RData::MX(data) => data,
+            RData::TXT(data) => data,
+            RData::AAAA(data) => data,
+            RData::SRV(data) => data,
+            RData::Unknown(data) => data,
+        }
+    }
+}
+
+impl ResourceData for RData {
+    fn serialise(&self) -> Vec<u8> {
+        self.inner().serialise()
+    }
+
+    fn write(&self, writer: &mut WireWriter) {
+        self.inner().write(writer)
+    }
+}
+
+impl Display for RData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self.inner(), f)
+    }
+}
+
+/// Reads the RDATA of a resource record of the given type, which must use exactly `rd_length`
+/// bytes. Types which this implementation does not interpret are kept as raw data.
+pub fn read_resource_data(
+    rr_type: &RRType<u16>,
+    reader: &mut WireReader,
+    rd_length: u16,
+) -> Result<RData, RDNSError> {
+    let start = reader.position();
+
+    // A known code given as UNKNOWN is still decoded, so it can't end up held as raw data
+    let rdata = match &RRType::from_value(rr_type.value()) {
+        RRType::A => RData::A(AliasResourceData::read(reader, rd_length)?),
+        RRType::NS => RData::NS(NameServerResourceData::read(reader)?),
+        RRType::MD => RData::MD(MailDestinationResourceData::read(reader)?),
+        RRType::MF => RData::MF(MailForwarderResourceData::read(reader)?),
+        RRType::CNAME => RData::CNAME(CNameResourceData::read(reader)?),
+        RRType::SOA => RData::SOA(SOAResourceData::read(reader)?),
+        RRType::MB => RData::MB(MailboxResourceData::read(reader)?),
+        RRType::MG => RData::MG(MailGroupResourceData::read(reader)?),
+        RRType::MR => RData::MR(MailRenameResourceData::read(reader)?),
+        RRType::NULL => RData::NULL(NullResourceData::read(reader, rd_length)?),
+        RRType::WKS => RData::WKS(WellKnownServiceResourceData::read(reader, rd_length)?),
+        RRType::PTR => RData::PTR(PointerResourceData::read(reader)?),
+        RRType::HINFO => RData::HINFO(HInfoResourceData::read(reader)?),
+        RRType::MINFO => RData::MINFO(MailInfoResourceData::read(reader)?),
+        RRType::MX => RData::MX(MailExchangeResourceData::read(reader)?),
+        RRType::TXT => RData::TXT(TextResourceData::read(reader, rd_length)?),
+        RRType::AAAA => RData::AAAA(Ipv6AddressResourceData::read(reader, rd_length)?),
+        RRType::SRV => RData::SRV(ServiceResourceData::read(reader)?),
+        RRType::UNKNOWN(value) => RData::Unknown(UnknownResourceData {
+            rr_type: *value,
+            data: RawResourceData::read(reader, rd_length)?,
+        }),
+    };
+
+    let consumed = reader.position() - start;
+    if consumed != rd_length as usize {
+        return Err(RDNSError::MessageFormatError(format!(
+            "{:?} record data should be {} bytes but {} were used",
+            rr_type, rd_length, consumed
+        )));
+    }
+
+    Ok(rdata)
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::error::RDNSError;
+    use crate::name::Name;
     use crate::record::{
-        CNameResourceData, HInfoResourceData, MailExchangeResourceData, RawResourceData,
-        ResourceData,
+        read_resource_data, AliasResourceData, CNameResourceData, HInfoResourceData,
+        Ipv6AddressResourceData, MailExchangeResourceData, MailInfoResourceData,
+        MailboxResourceData, NameServerResourceData, NullResourceData, PointerResourceData, RData,
+        RawResourceData, ResourceData, SOAResourceData, ServiceResourceData, TextResourceData,
+        WellKnownServiceResourceData,
     };
     use crate::test;
+    use crate::wire::{WireReader, WireWriter};
+    use crate::RRType;
+    use std::net::{Ipv4Addr, Ipv6Addr};
 
     #[test]
     fn round_trip_raw_record() {
         let input = "example.com".as_bytes();
-        let raw = RawResourceData::read(input).unwrap();
+        let raw = RawResourceData::read(&mut WireReader::new(input), input.len() as u16).unwrap();
         assert_eq!(input, raw.serialise());
     }
 
+    #[test]
+    fn display_raw_record() {
+        assert_eq!(
+            "\\# 4 0A000001",
+            RawResourceData(vec![10, 0, 0, 1]).to_string()
+        );
+        assert_eq!("\\# 0", RawResourceData(Vec::new()).to_string());
+    }
+
+    #[test]
+    fn display_resource_data() {
+        let name = Name::try_from("host.example.com.".to_string()).unwrap();
+
+        assert_eq!(
+            "192.168.0.1",
+            AliasResourceData(Ipv4Addr::new(192, 168, 0, 1)).to_string()
+        );
+        assert_eq!(
+            "2001:db8::1",
+            Ipv6AddressResourceData(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1)).to_string()
+        );
+        assert_eq!(
+            "host.example.com.",
+            CNameResourceData(name.clone()).to_string()
+        );
+        assert_eq!(
+            "10 host.example.com.",
+            MailExchangeResourceData {
+                preference: 10,
+                exchange: name.clone(),
+            }
+            .to_string()
+        );
+        assert_eq!(
+            "0 5 5060 host.example.com.",
+            ServiceResourceData {
+                priority: 0,
+                weight: 5,
+                port: 5060,
+                target: name.clone(),
+            }
+            .to_string()
+        );
+        assert_eq!(
+            "host.example.com. host.example.com. 1 7200 600 3600000 60",
+            SOAResourceData {
+                primary_name: name.clone(),
+                responsible_name: name.clone(),
+                serial: 1,
+                refresh: 7200,
+                retry: 600,
+                expire: 3600000,
+                minimum: 60,
+            }
+            .to_string()
+        );
+        assert_eq!(
+            "host.example.com. host.example.com.",
+            MailInfoResourceData {
+                responsible_mailbox: name.clone(),
+                error_mailbox: name,
+            }
+            .to_string()
+        );
+        assert_eq!(
+            "10.0.0.1 6 21 25",
+            WellKnownServiceResourceData::from_ports(Ipv4Addr::new(10, 0, 0, 1), 6, &[21, 25])
+                .to_string()
+        );
+        assert_eq!("\\# 2 CAFE", NullResourceData(vec![0xCA, 0xFE]).to_string());
+    }
+
+    #[test]
+    fn display_soa_timers_with_units() {
+        let name = Name::try_from("example.com.".to_string()).unwrap();
+        let soa = SOAResourceData {
+            primary_name: name.clone(),
+            responsible_name: name,
+            serial: 2024010101,
+            refresh: 7200,
+            retry: 900,
+            expire: 1209600,
+            minimum: 3600,
+        };
+
+        assert_eq!(
+            "example.com. example.com. 2024010101 2h 15m 2w 1h",
+            soa.display_with_units()
+        );
+        assert_eq!(
+            "example.com. example.com. 2024010101 7200 900 1209600 3600",
+            soa.to_string()
+        );
+        assert_eq!(
+            "example.com. example.com. 2024010101 2h -1 2w 1h",
+            SOAResourceData { retry: -1, ..soa }.display_with_units()
+        );
+    }
+
+    #[test]
+    fn display_character_strings_with_escapes() {
+        let txt = TextResourceData(vec![
+            b"v=spf1 -all".to_vec(),
+            b"say \"hi\" \\".to_vec(),
+            vec![b'a', 0, 200],
+            Vec::new(),
+        ]);
+        assert_eq!(
+            r#""v=spf1 -all" "say \"hi\" \\" "a\000\200" """#,
+            txt.to_string()
+        );
+
+        let hinfo = HInfoResourceData::new(b"INTEL-386".to_vec(), b"Linux 6".to_vec()).unwrap();
+        assert_eq!(r#""INTEL-386" "Linux 6""#, hinfo.to_string());
+    }
+
+    #[test]
+    fn round_trip_alias() {
+        let input = [192, 168, 0, 1];
+        let alias = AliasResourceData::read(&mut WireReader::new(&input), 4).unwrap();
+        assert_eq!(Ipv4Addr::new(192, 168, 0, 1), alias.0);
+        assert_eq!(input.to_vec(), alias.serialise());
+    }
+
+    #[test]
+    fn alias_with_wrong_length() {
+        let input = [192, 168, 0, 1, 2];
+        let err = AliasResourceData::read(&mut WireReader::new(&input), 5).unwrap_err();
+        assert!(matches!(err, RDNSError::MessageFormatError(_)));
+    }
+
+    #[test]
+    fn alias_with_truncated_data() {
+        let input = [192, 168];
+        let err = AliasResourceData::read(&mut WireReader::new(&input), 4).unwrap_err();
+        assert!(matches!(err, RDNSError::MessageTruncated(2)));
+    }
+
+    #[test]
+    fn round_trip_ipv6_address() {
+        let input = [0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+        let aaaa = Ipv6AddressResourceData::read(&mut WireReader::new(&input), 16).unwrap();
+        assert_eq!(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1), aaaa.0);
+        assert_eq!(input.to_vec(), aaaa.serialise());
+    }
+
+    #[test]
+    fn ipv6_address_with_wrong_length() {
+        let input = [192, 168, 0, 1];
+        let err = Ipv6AddressResourceData::read(&mut WireReader::new(&input), 4).unwrap_err();
+        assert!(matches!(err, RDNSError::MessageFormatError(_)));
+    }
+
     #[test]
     fn round_trip_cname() {
-        let input = "example.com";
-        let cname = CNameResourceData::read(input.as_bytes()).unwrap();
-        assert_eq!(test::dirty_to_bytes(input.to_string()), cname.serialise());
+        let input = test::dirty_to_bytes("example.com.".to_string());
+        let cname = CNameResourceData::read(&mut WireReader::new(&input)).unwrap();
+        assert_eq!(input, cname.serialise());
+    }
+
+    #[test]
+    fn read_cname_with_compressed_name() {
+        let mut input = test::dirty_to_bytes("example.com.".to_string());
+        input.extend_from_slice(&[3, b'w', b'w', b'w', 0xC0, 0]);
+
+        let mut reader = WireReader::new(&input);
+        reader.read_slice(13).unwrap();
+        let cname = read_resource_data(&RRType::CNAME, &mut reader, 6).unwrap();
+
+        assert_eq!(
+            test::dirty_to_bytes("www.example.com.".to_string()),
+            cname.serialise()
+        );
+    }
+
+    #[test]
+    fn round_trip_soa() {
+        let soa = SOAResourceData {
+            primary_name: Name::try_from("ns1.example.com.".to_string()).unwrap(),
+            responsible_name: Name::try_from("hostmaster.example.com.".to_string()).unwrap(),
+            serial: 2023010101,
+            refresh: 7200,
+            retry: 600,
+            expire: 3600000,
+            minimum: 60,
+        };
+        let input = soa.serialise();
+
+        let read = SOAResourceData::read(&mut WireReader::new(&input)).unwrap();
+        assert_eq!(2023010101, read.serial);
+        assert_eq!(7200, read.refresh);
+        assert_eq!(600, read.retry);
+        assert_eq!(3600000, read.expire);
+        assert_eq!(60, read.minimum);
+        assert_eq!(input, read.serialise());
+    }
+
+    #[test]
+    fn soa_with_truncated_data() {
+        let mut input = test::dirty_to_bytes("ns1.example.com.".to_string());
+        input.extend(test::dirty_to_bytes("hostmaster.example.com.".to_string()));
+        input.extend_from_slice(&[0, 0, 0, 1]);
+
+        let err = read_resource_data(&RRType::SOA, &mut WireReader::new(&input), 50).unwrap_err();
+        assert!(matches!(err, RDNSError::MessageTruncated(_)));
     }
 
     #[test]
     fn round_trip_hinfo() {
-        let input = "\"INTEL-386\" / \"WIN32\"".as_bytes();
-        let hinfo = HInfoResourceData::read(input).unwrap();
+        let mut input = vec![9];
+        input.extend_from_slice(b"INTEL-386");
+        input.push(5);
+        input.extend_from_slice(b"WIN32");
+
+        let hinfo = HInfoResourceData::read(&mut WireReader::new(&input)).unwrap();
+        assert_eq!(b"INTEL-386", hinfo.cpu());
+        assert_eq!(b"WIN32", hinfo.os());
         assert_eq!(input, hinfo.serialise());
     }
 
+    #[test]
+    fn hinfo_longer_than_a_character_string() {
+        assert!(HInfoResourceData::new(vec![b'x'; 255], b"Linux".to_vec()).is_ok());
+        assert!(matches!(
+            HInfoResourceData::new(b"INTEL-386".to_vec(), vec![b'x'; 256]),
+            Err(RDNSError::CharacterStringTooLong(256))
+        ));
+    }
+
+    #[test]
+    fn hinfo_missing_os() {
+        let input = [3, b'V', b'A', b'X'];
+        let err = read_resource_data(&RRType::HINFO, &mut WireReader::new(&input), 4).unwrap_err();
+        assert!(matches!(err, RDNSError::MessageTruncated(4)));
+    }
+
+    #[test]
+    fn round_trip_mail_info() {
+        let mut input = test::dirty_to_bytes("owner.example.com.".to_string());
+        input.extend(test::dirty_to_bytes("errors.example.com.".to_string()));
+
+        let minfo = MailInfoResourceData::read(&mut WireReader::new(&input)).unwrap();
+        assert_eq!(
+            "owner.example.com.",
+            <Name as Into<String>>::into(minfo.responsible_mailbox.clone())
+        );
+        assert_eq!(
+            "errors.example.com.",
+            <Name as Into<String>>::into(minfo.error_mailbox.clone())
+        );
+        assert_eq!(input, minfo.serialise());
+    }
+
+    #[test]
+    fn round_trip_mailbox_types() {
+        let input = test::dirty_to_bytes("mail.example.com.".to_string());
+        for rr_type in [RRType::MD, RRType::MF, RRType::MB, RRType::MG, RRType::MR] {
+            let rdata = read_resource_data(&rr_type, &mut WireReader::new(&input), 18).unwrap();
+            assert_eq!(input, rdata.serialise());
+        }
+    }
+
+    #[test]
+    fn round_trip_well_known_service() {
+        let input = [10, 0, 0, 1, 6, 0b0000_0000, 0b0000_0000, 0b0010_0000];
+        let wks = WellKnownServiceResourceData::read(&mut WireReader::new(&input), 8).unwrap();
+        assert_eq!(Ipv4Addr::new(10, 0, 0, 1), wks.address);
+        assert_eq!(6, wks.protocol);
+        assert_eq!(vec![18], wks.ports());
+        assert_eq!(input.to_vec(), wks.serialise());
+    }
+
+    #[test]
+    fn well_known_service_from_ports() {
+        let wks =
+            WellKnownServiceResourceData::from_ports(Ipv4Addr::new(10, 0, 0, 1), 6, &[25, 21, 0]);
+        assert_eq!(vec![0x80, 0, 0x04, 0x40], wks.bitmap);
+        assert_eq!(vec![0, 21, 25], wks.ports());
+    }
+
+    #[test]
+    fn well_known_service_too_short() {
+        let err = WellKnownServiceResourceData::read(&mut WireReader::new(&[10, 0, 0, 1]), 4)
+            .unwrap_err();
+        assert!(matches!(err, RDNSError::MessageFormatError(_)));
+    }
+
+    #[test]
+    fn round_trip_null() {
+        let input = [0xDE, 0xAD, 0xBE, 0xEF];
+        let null = read_resource_data(&RRType::NULL, &mut WireReader::new(&input), 4).unwrap();
+        assert_eq!(input.to_vec(), null.serialise());
+    }
+
     #[test]
     fn round_trip_mail_exchange() {
-        let input = "mail.example.com";
-        let mx = MailExchangeResourceData::read(10, input.as_bytes()).unwrap();
-
-        let mut expected = Vec::new();
-        expected.push(0u8);
-        expected.push(10);
-        expected.extend(test::dirty_to_bytes(input.to_string()));
-        assert_eq!(expected, mx.serialise());
+        let mut input = vec![0u8, 10];
+        input.extend(test::dirty_to_bytes("mail.example.com.".to_string()));
+
+        let mx = MailExchangeResourceData::read(&mut WireReader::new(&input)).unwrap();
+        assert_eq!(10, mx.preference);
+        assert_eq!(input, mx.serialise());
+    }
+
+    #[test]
+    fn round_trip_text() {
+        let input = [3, b'a', b'b', b'c', 0, 5, b'h', b'e', b'l', b'l', b'o'];
+        let txt = TextResourceData::read(&mut WireReader::new(&input), 11).unwrap();
+        assert_eq!(vec![b"abc".to_vec(), Vec::new(), b"hello".to_vec()], txt.0);
+        assert_eq!(input.to_vec(), txt.serialise());
+    }
+
+    #[test]
+    fn text_must_not_be_empty() {
+        let err = TextResourceData::read(&mut WireReader::new(&[]), 0).unwrap_err();
+        assert!(matches!(err, RDNSError::MessageFormatError(_)));
+    }
+
+    #[test]
+    fn text_string_longer_than_data() {
+        let input = [5, b'a', b'b', b'c'];
+        let err = read_resource_data(&RRType::TXT, &mut WireReader::new(&input), 4).unwrap_err();
+        assert!(matches!(err, RDNSError::MessageTruncated(4)));
+    }
+
+    #[test]
+    fn text_longer_than_a_character_string_is_split() {
+        let txt = TextResourceData(vec![vec![b'a'; 300]]);
+        let bytes = txt.serialise();
+
+        assert_eq!(302, bytes.len());
+        assert_eq!(255, bytes[0]);
+        assert_eq!(45, bytes[256]);
+
+        let read = TextResourceData::read(&mut WireReader::new(&bytes), 302).unwrap();
+        assert_eq!(vec![vec![b'a'; 255], vec![b'a'; 45]], read.0);
+    }
+
+    #[test]
+    fn round_trip_service() {
+        let mut input = vec![0u8, 10, 0, 60, 0x13, 0xC4];
+        input.extend(test::dirty_to_bytes("sip.example.com.".to_string()));
+
+        let srv = ServiceResourceData::read(&mut WireReader::new(&input)).unwrap();
+        assert_eq!(10, srv.priority);
+        assert_eq!(60, srv.weight);
+        assert_eq!(5060, srv.port);
+        assert_eq!(input, srv.serialise());
+    }
+
+    #[test]
+    fn service_target_is_not_compressed() {
+        let target = Name::try_from("sip.example.com.".to_string()).unwrap();
+        let srv = ServiceResourceData {
+            priority: 0,
+            weight: 0,
+            port: 5060,
+            target: target.clone(),
+        };
+
+        let mut writer = WireWriter::new();
+        target.write(&mut writer);
+        srv.write(&mut writer);
+
+        let mut expected = test::dirty_to_bytes("sip.example.com.".to_string());
+        expected.extend(srv.serialise());
+        assert_eq!(expected, writer.into_bytes());
+    }
+
+    #[test]
+    fn resource_data_shorter_than_length() {
+        let mut input = test::dirty_to_bytes("example.com.".to_string());
+        input.extend_from_slice(&[0, 0]);
+
+        let err = read_resource_data(&RRType::NS, &mut WireReader::new(&input), 15).unwrap_err();
+        assert!(matches!(err, RDNSError::MessageFormatError(_)));
+    }
+
+    #[test]
+    fn resource_data_longer_than_length() {
+        let input = test::dirty_to_bytes("example.com.".to_string());
+
+        let err = read_resource_data(&RRType::PTR, &mut WireReader::new(&input), 5).unwrap_err();
+        assert!(matches!(err, RDNSError::MessageFormatError(_)));
+    }
+
+    #[test]
+    fn known_type_code_given_as_unknown_is_decoded() {
+        let input = [10, 0, 0, 1];
+        let rdata =
+            read_resource_data(&RRType::UNKNOWN(1), &mut WireReader::new(&input), 4).unwrap();
+        assert_eq!(
+            RData::A(AliasResourceData(Ipv4Addr::new(10, 0, 0, 1))),
+            rdata
+        );
+    }
+
+    #[test]
+    fn unknown_constructor_decodes_known_type_codes() {
+        assert_eq!(
+            RData::A(AliasResourceData(Ipv4Addr::new(10, 0, 0, 1))),
+            RData::unknown(1, RawResourceData(vec![10, 0, 0, 1])).unwrap()
+        );
+
+        let rdata = RData::unknown(65280, RawResourceData(vec![0xAB])).unwrap();
+        assert_eq!(RRType::UNKNOWN(65280), rdata.rr_type());
+
+        assert!(RData::unknown(1, RawResourceData(vec![10, 0, 0])).is_err());
+    }
+
+    #[test]
+    fn unknown_type_read_as_raw() {
+        let input = [1, 2, 3];
+        let raw =
+            read_resource_data(&RRType::UNKNOWN(65280), &mut WireReader::new(&input), 3).unwrap();
+        assert_eq!(
+            RData::unknown(65280, RawResourceData(input.to_vec())).unwrap(),
+            raw
+        );
+        assert_eq!(RRType::UNKNOWN(65280), raw.rr_type());
+    }
+
+    #[test]
+    fn read_typed_resource_data() {
+        let input = [10, 0, 0, 1];
+        let rdata = read_resource_data(&RRType::A, &mut WireReader::new(&input), 4).unwrap();
+
+        match &rdata {
+            RData::A(AliasResourceData(address)) => {
+                assert_eq!(Ipv4Addr::new(10, 0, 0, 1), *address)
+            }
+            other => panic!("expected an A record but got {:?}", other),
+        }
+        assert_eq!(RRType::A, rdata.rr_type());
+        assert_eq!("10.0.0.1", rdata.to_string());
+    }
+
+    #[test]
+    fn resource_data_type_matches_variant() {
+        let name = Name::try_from("example.com.".to_string()).unwrap();
+
+        assert_eq!(
+            RRType::NS,
+            RData::NS(NameServerResourceData(name.clone())).rr_type()
+        );
+        assert_eq!(
+            RRType::PTR,
+            RData::PTR(PointerResourceData(name.clone())).rr_type()
+        );
+        assert_eq!(RRType::MB, RData::MB(MailboxResourceData(name)).rr_type());
+        assert_eq!(
+            RRType::NULL,
+            RData::NULL(NullResourceData(Vec::new())).rr_type()
+        );
+        assert_eq!(
+            RRType::TXT,
+            RData::TXT(TextResourceData(Vec::new())).rr_type()
+        );
     }
 }
@@ -0,0 +1,244 @@
+use crate::error::RDNSError;
+use crate::name::Name;
+use crate::record::RData;
+use crate::{RRClass, RRType, ResourceRecord};
+use std::collections::HashMap;
+
+/// A resource record set (RRset), the records with the same owner name, class and type
+/// (RFC 2181 section 5). All records in the set share a single TTL and no two hold the same data.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RRSet {
+    name: Name,
+    class: RRClass<u16>,
+    ttl: i32,
+    rdata: Vec<RData>,
+}
+
+impl RRSet {
+    /// Starts a new RRset containing a single record
+    pub fn new(record: ResourceRecord) -> Self {
+        RRSet {
+            name: record.name,
+            class: record.class,
+            ttl: record.ttl,
+            rdata: vec![record.rdata],
+        }
+    }
+
+    /// Adds a record to this set. A record with the same data as one already in the set is
+    /// dropped, and the TTL of the set becomes the lowest of all the records added (RFC 2181
+    /// section 5.2). Returns whether the record's data was added.
+    pub fn add(&mut self, record: ResourceRecord) -> Result<bool, RDNSError> {
+        if !self.contains_record(&record) {
+            return Err(RDNSError::RRSetMismatch(format!(
+                "{} {} {}",
+                self.name,
+                self.class,
+                self.rr_type()
+            )));
+        }
+
+        self.ttl = self.ttl.min(record.ttl);
+
+        if self.rdata.contains(&record.rdata) {
+            return Ok(false);
+        }
+
+        self.rdata.push(record.rdata);
+        Ok(true)
+    }
+
+    /// Whether a record has the owner name, class and type of this set, regardless of its data
+    pub fn contains_record(&self, record: &ResourceRecord) -> bool {
+        self.name == record.name && self.class == record.class && self.rr_type() == record.rr_type()
+    }
+
+    pub fn name(&self) -> &Name {
+        &self.name
+    }
+
+    pub fn rr_type(&self) -> RRType<u16> {
+        self.rdata[0].rr_type()
+    }
+
+    pub fn class(&self) -> &RRClass<u16> {
+        &self.class
+    }
+
+    pub fn ttl(&self) -> i32 {
+        self.ttl
+    }
+
+    /// The data of each record in the set, in the order they were added
+    pub fn rdata(&self) -> &[RData] {
+        &self.rdata
+    }
+
+    pub fn len(&self) -> usize {
+        self.rdata.len()
+    }
+
+    /// An RRset always holds at least one record so this is never true
+    pub fn is_empty(&self) -> bool {
+        self.rdata.is_empty()
+    }
+
+    /// A copy of this set with a different TTL, used for negative answers (RFC 2308 section 3)
+    pub fn with_ttl(&self, ttl: i32) -> RRSet {
+        RRSet {
+            ttl,
+            ..self.clone()
+        }
+    }
+
+    /// A copy of this set owned by a different name, as when answering from a wildcard
+    pub fn with_name(&self, name: Name) -> RRSet {
+        RRSet {
+            name,
+            ..self.clone()
+        }
+    }
+
+    /// The individual records in this set, all with the set's TTL
+    pub fn records(&self) -> Vec<ResourceRecord> {
+        self.rdata
+            .iter()
+            .map(|rdata| ResourceRecord {
+                name: self.name.clone(),
+                class: self.class.clone(),
+                ttl: self.ttl,
+                rdata: rdata.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Groups records into RRsets. Sets are returned in the order their first record appears, with
+/// duplicate records removed and TTLs normalised as for [`RRSet::add`].
+pub fn group(records: impl IntoIterator<Item = ResourceRecord>) -> Vec<RRSet> {
+    let mut sets: Vec<RRSet> = Vec::new();
+    let mut index: HashMap<(Name, u16, u16), usize> = HashMap::new();
+
+    for record in records {
+        let key = (
+            record.name.clone(),
+            record.class.value(),
+            record.rr_type().value(),
+        );
+
+        match index.get(&key) {
+            Some(&i) => {
+                // The key guarantees the record belongs to the set
+                sets[i].add(record).unwrap();
+            }
+            None => {
+                index.insert(key, sets.len());
+                sets.push(RRSet::new(record));
+            }
+        }
+    }
+
+    sets
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::RDNSError;
+    use crate::rrset::{group, RRSet};
+    use crate::test::{a_record, name, ns_record};
+    use crate::{RRClass, RRType};
+
+    #[test]
+    fn add_records_to_set() {
+        let mut set = RRSet::new(a_record("www.example.com.", 300, 1));
+        assert!(set.add(a_record("WWW.example.com.", 300, 2)).unwrap());
+
+        assert_eq!(2, set.len());
+        assert_eq!(RRType::A, set.rr_type());
+        assert_eq!(RRClass::IN, *set.class());
+        assert_eq!(300, set.ttl());
+        assert_eq!(
+            vec![
+                a_record("www.example.com.", 300, 1),
+                a_record("www.example.com.", 300, 2)
+            ],
+            set.records()
+        );
+    }
+
+    #[test]
+    fn duplicate_records_are_removed() {
+        let mut set = RRSet::new(a_record("www.example.com.", 300, 1));
+        assert!(!set.add(a_record("www.example.com.", 300, 1)).unwrap());
+
+        assert_eq!(1, set.len());
+    }
+
+    #[test]
+    fn ttl_is_normalised_to_lowest() {
+        let mut set = RRSet::new(a_record("www.example.com.", 300, 1));
+        set.add(a_record("www.example.com.", 60, 2)).unwrap();
+        set.add(a_record("www.example.com.", 3600, 3)).unwrap();
+        set.add(a_record("www.example.com.", 30, 1)).unwrap();
+
+        assert_eq!(30, set.ttl());
+        assert!(set.records().iter().all(|r| r.ttl == 30));
+    }
+
+    #[test]
+    fn record_from_another_set_is_rejected() {
+        let mut set = RRSet::new(a_record("www.example.com.", 300, 1));
+
+        let err = set.add(a_record("mail.example.com.", 300, 1)).unwrap_err();
+        assert!(matches!(err, RDNSError::RRSetMismatch(_)));
+
+        let err = set
+            .add(ns_record("www.example.com.", "ns1.example.com."))
+            .unwrap_err();
+        assert!(matches!(err, RDNSError::RRSetMismatch(_)));
+
+        let mut record = a_record("www.example.com.", 300, 2);
+        record.class = RRClass::CH;
+        let err = set.add(record).unwrap_err();
+        assert!(matches!(err, RDNSError::RRSetMismatch(_)));
+    }
+
+    #[test]
+    fn copy_set_with_new_name_and_ttl() {
+        let set = RRSet::new(a_record("*.example.com.", 300, 1));
+
+        let copy = set.with_name(name("www.example.com.")).with_ttl(60);
+        assert_eq!(vec![a_record("www.example.com.", 60, 1)], copy.records());
+    }
+
+    #[test]
+    fn group_records_into_sets() {
+        let sets = group(vec![
+            ns_record("example.com.", "ns1.example.com."),
+            a_record("www.example.com.", 300, 1),
+            ns_record("Example.com.", "ns2.example.com."),
+            a_record("www.example.com.", 60, 2),
+            a_record("www.example.com.", 300, 1),
+            a_record("example.com.", 300, 1),
+        ]);
+
+        assert_eq!(3, sets.len());
+
+        assert_eq!(RRType::NS, sets[0].rr_type());
+        assert_eq!(2, sets[0].len());
+
+        assert_eq!(RRType::A, sets[1].rr_type());
+        assert_eq!("www.example.com.", sets[1].name().to_string());
+        assert_eq!(2, sets[1].len());
+        assert_eq!(60, sets[1].ttl());
+
+        assert_eq!(RRType::A, sets[2].rr_type());
+        assert_eq!("example.com.", sets[2].name().to_string());
+        assert_eq!(1, sets[2].len());
+    }
+
+    #[test]
+    fn group_no_records() {
+        assert!(group(Vec::new()).is_empty());
+    }
+}
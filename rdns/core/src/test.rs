@@ -1,3 +1,10 @@
+use crate::name::Name;
+use crate::record::{
+    AliasResourceData, CNameResourceData, MailExchangeResourceData, NameServerResourceData, RData,
+};
+use crate::{RRClass, ResourceRecord};
+use std::net::Ipv4Addr;
+
 #[cfg(test)]
 pub fn dirty_to_bytes(repr: String) -> Vec<u8> {
     let mut result = Vec::with_capacity(repr.len());
@@ -13,3 +20,46 @@ pub fn dirty_to_bytes(repr: String) -> Vec<u8> {
 
     result
 }
+
+pub fn name(name: &str) -> Name {
+    Name::try_from(name.to_string()).unwrap()
+}
+
+pub fn a_record(owner: &str, ttl: i32, host: u8) -> ResourceRecord {
+    ResourceRecord {
+        name: name(owner),
+        class: RRClass::IN,
+        ttl,
+        rdata: RData::A(AliasResourceData(Ipv4Addr::new(10, 0, 0, host))),
+    }
+}
+
+pub fn ns_record(owner: &str, server: &str) -> ResourceRecord {
+    ResourceRecord {
+        name: name(owner),
+        class: RRClass::IN,
+        ttl: 300,
+        rdata: RData::NS(NameServerResourceData(name(server))),
+    }
+}
+
+pub fn mx_record(owner: &str) -> ResourceRecord {
+    ResourceRecord {
+        name: name(owner),
+        class: RRClass::IN,
+        ttl: 300,
+        rdata: RData::MX(MailExchangeResourceData {
+            preference: 10,
+            exchange: name("mail.example.com."),
+        }),
+    }
+}
+
+pub fn cname_record(owner: &str, target: &str) -> ResourceRecord {
+    ResourceRecord {
+        name: name(owner),
+        class: RRClass::IN,
+        ttl: 300,
+        rdata: RData::CNAME(CNameResourceData(name(target))),
+    }
+}
@@ -0,0 +1,128 @@
+//! TTLs and other time intervals in master files, which may be written as a number of seconds or
+//! with the BIND style units `s`, `m`, `h`, `d` and `w`, such as `1h30m`
+
+const UNITS: [(u8, u32); 5] = [
+    (b'w', 7 * 24 * 60 * 60),
+    (b'd', 24 * 60 * 60),
+    (b'h', 60 * 60),
+    (b'm', 60),
+    (b's', 1),
+];
+
+/// Parses a time interval into seconds. Units are case-insensitive and can be combined, with any
+/// trailing number without a unit taken as seconds. Returns `None` if the text is not a valid
+/// interval or the total does not fit in 32 bits.
+pub fn parse(text: &str) -> Option<u32> {
+    if text.is_empty() {
+        return None;
+    }
+
+    let mut total: u32 = 0;
+    let mut current: Option<u32> = None;
+
+    for ch in text.bytes() {
+        if ch.is_ascii_digit() {
+            let digit = (ch - b'0') as u32;
+            current = Some(current.unwrap_or(0).checked_mul(10)?.checked_add(digit)?);
+        } else {
+            let (_, multiplier) = UNITS
+                .iter()
+                .find(|(unit, _)| *unit == ch.to_ascii_lowercase())?;
+            total = total.checked_add(current?.checked_mul(*multiplier)?)?;
+            current = None;
+        }
+    }
+
+    total.checked_add(current.unwrap_or(0))
+}
+
+/// Formats a number of seconds using the largest units which divide it, such as `1h30m`
+pub fn format(seconds: u32) -> String {
+    if seconds == 0 {
+        return "0s".to_string();
+    }
+
+    let mut result = String::new();
+    let mut remaining = seconds;
+    for (unit, multiplier) in UNITS {
+        if remaining >= multiplier {
+            result.push_str(&(remaining / multiplier).to_string());
+            result.push(unit as char);
+            remaining %= multiplier;
+        }
+    }
+
+    result
+}
+
+/// Formats a signed interval such as a record TTL with units. Negative values have no units so
+/// they are written as a plain number.
+pub fn format_signed(seconds: i32) -> String {
+    match u32::try_from(seconds) {
+        Ok(seconds) => format(seconds),
+        Err(_) => seconds.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ttl::{format, format_signed, parse};
+
+    #[test]
+    fn parse_seconds() {
+        assert_eq!(Some(3600), parse("3600"));
+        assert_eq!(Some(0), parse("0"));
+    }
+
+    #[test]
+    fn parse_units() {
+        assert_eq!(Some(30), parse("30s"));
+        assert_eq!(Some(300), parse("5m"));
+        assert_eq!(Some(3600), parse("1h"));
+        assert_eq!(Some(172800), parse("2d"));
+        assert_eq!(Some(604800), parse("1w"));
+    }
+
+    #[test]
+    fn parse_combined_units_ignoring_case() {
+        assert_eq!(Some(5400), parse("1h30m"));
+        assert_eq!(Some(5400), parse("1H30M"));
+        assert_eq!(Some(694861), parse("1w1d1h1m1s"));
+        assert_eq!(Some(3630), parse("1h30"));
+    }
+
+    #[test]
+    fn parse_invalid_intervals() {
+        assert_eq!(None, parse(""));
+        assert_eq!(None, parse("h"));
+        assert_eq!(None, parse("1x"));
+        assert_eq!(None, parse("1hh"));
+        assert_eq!(None, parse("-1"));
+        assert_eq!(None, parse("7102w"));
+        assert_eq!(None, parse("4294967296"));
+    }
+
+    #[test]
+    fn format_with_units() {
+        assert_eq!("0s", format(0));
+        assert_eq!("45s", format(45));
+        assert_eq!("1h30m", format(5400));
+        assert_eq!("1w1d1h1m1s", format(694861));
+        assert_eq!("2w", format(1209600));
+    }
+
+    #[test]
+    fn format_signed_with_units() {
+        assert_eq!("1h30m", format_signed(5400));
+        assert_eq!("0s", format_signed(0));
+        assert_eq!("-1", format_signed(-1));
+        assert_eq!("-2147483648", format_signed(i32::MIN));
+    }
+
+    #[test]
+    fn round_trip_format_and_parse() {
+        for seconds in [1, 59, 60, 86399, 3600000, 2147483647] {
+            assert_eq!(Some(seconds), parse(&format(seconds)));
+        }
+    }
+}
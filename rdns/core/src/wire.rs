@@ -0,0 +1,205 @@
+use crate::error::RDNSError;
+use bytes::BufMut;
+use std::collections::HashMap;
+
+/// Reads wire format data from a DNS message. The whole message is retained, rather than just the
+/// part being read, so that compressed names can be resolved against earlier parts of the message.
+pub struct WireReader<'a> {
+    message: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> WireReader<'a> {
+    pub fn new(message: &'a [u8]) -> Self {
+        WireReader { message, pos: 0 }
+    }
+
+    /// Creates a reader for the same message which starts at a different offset, used to follow
+    /// compression pointers
+    pub fn at(&self, pos: usize) -> Self {
+        WireReader {
+            message: self.message,
+            pos,
+        }
+    }
+
+    /// The complete message being read
+    pub fn message(&self) -> &'a [u8] {
+        self.message
+    }
+
+    /// The offset of the next octet to be read
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// The number of octets left to read
+    pub fn remaining(&self) -> usize {
+        self.message.len() - self.pos
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, RDNSError> {
+        Ok(self.read_slice(1)?[0])
+    }
+
+    pub fn read_u16(&mut self) -> Result<u16, RDNSError> {
+        let bytes = self.read_slice(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, RDNSError> {
+        let bytes = self.read_slice(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    pub fn read_i32(&mut self) -> Result<i32, RDNSError> {
+        self.read_u32().map(|v| v as i32)
+    }
+
+    /// Reads the next `len` octets, failing if the message does not contain that many
+    pub fn read_slice(&mut self, len: usize) -> Result<&'a [u8], RDNSError> {
+        if self.remaining() < len {
+            return Err(RDNSError::MessageTruncated(self.message.len()));
+        }
+
+        let slice = &self.message[self.pos..self.pos + len];
+        self.pos += len;
+
+        Ok(slice)
+    }
+}
+
+/// Compression pointers can only address the first 16K of a message
+const MAX_POINTER_OFFSET: usize = 0x3FFF;
+
+/// Builds a DNS message in wire format
+#[derive(Default)]
+pub struct WireWriter {
+    buf: Vec<u8>,
+    /// Offsets of names already written to the message, keyed by their lowercase wire format so
+    /// that later names with a common suffix can point to them (RFC 1035 section 4.1.4)
+    names: HashMap<Vec<u8>, u16>,
+}
+
+impl WireWriter {
+    pub fn new() -> Self {
+        WireWriter::default()
+    }
+
+    /// The number of octets written so far
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        self.buf.put_u8(value);
+    }
+
+    pub fn write_u16(&mut self, value: u16) {
+        self.buf.put_u16(value);
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        self.buf.put_u32(value);
+    }
+
+    pub fn write_i32(&mut self, value: i32) {
+        self.buf.put_i32(value);
+    }
+
+    pub fn write_slice(&mut self, value: &[u8]) {
+        self.buf.put_slice(value);
+    }
+
+    /// Overwrites a two octet value which has already been written, used to fill in lengths once
+    /// the data they describe is known
+    pub fn set_u16(&mut self, pos: usize, value: u16) {
+        self.buf[pos..pos + 2].copy_from_slice(&value.to_be_bytes());
+    }
+
+    /// The offset of a previously written name which matches `name`, if there is one
+    pub fn find_name(&self, name: &[u8]) -> Option<u16> {
+        self.names.get(&name.to_ascii_lowercase()).copied()
+    }
+
+    /// Records that `name` is about to be written at the current offset so that later names can
+    /// point to it. Names beyond the range of a compression pointer are not recorded.
+    pub fn remember_name(&mut self, name: &[u8]) {
+        if self.buf.len() <= MAX_POINTER_OFFSET {
+            self.names
+                .entry(name.to_ascii_lowercase())
+                .or_insert(self.buf.len() as u16);
+        }
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::RDNSError;
+    use crate::wire::{WireReader, WireWriter};
+
+    #[test]
+    fn round_trip_numbers() {
+        let mut writer = WireWriter::new();
+        writer.write_u8(1);
+        writer.write_u16(0x0203);
+        writer.write_u32(0x04050607);
+        writer.write_i32(-1);
+
+        let bytes = writer.into_bytes();
+        assert_eq!(11, bytes.len());
+
+        let mut reader = WireReader::new(&bytes);
+        assert_eq!(1, reader.read_u8().unwrap());
+        assert_eq!(0x0203, reader.read_u16().unwrap());
+        assert_eq!(0x04050607, reader.read_u32().unwrap());
+        assert_eq!(-1, reader.read_i32().unwrap());
+        assert_eq!(0, reader.remaining());
+    }
+
+    #[test]
+    fn read_past_end_of_message() {
+        let mut reader = WireReader::new(&[0, 1, 2]);
+        reader.read_u16().unwrap();
+
+        let err = reader.read_u16().unwrap_err();
+        assert!(matches!(err, RDNSError::MessageTruncated(3)));
+    }
+
+    #[test]
+    fn set_length_after_writing() {
+        let mut writer = WireWriter::new();
+        writer.write_u16(0);
+        writer.write_slice(&[1, 2, 3]);
+        writer.set_u16(0, 3);
+
+        assert_eq!(vec![0, 3, 1, 2, 3], writer.into_bytes());
+    }
+
+    #[test]
+    fn find_remembered_name_ignoring_case() {
+        let mut writer = WireWriter::new();
+        writer.write_u16(0);
+        writer.remember_name(b"\x07Example\x03com\x00");
+
+        assert_eq!(Some(2), writer.find_name(b"\x07example\x03COM\x00"));
+        assert_eq!(None, writer.find_name(b"\x03com\x00"));
+    }
+
+    #[test]
+    fn names_out_of_pointer_range_are_not_remembered() {
+        let mut writer = WireWriter::new();
+        writer.write_slice(&[0; 0x4000]);
+        writer.remember_name(b"\x03com\x00");
+
+        assert_eq!(None, writer.find_name(b"\x03com\x00"));
+    }
+}
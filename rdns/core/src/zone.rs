@@ -0,0 +1,594 @@
+use crate::error::RDNSError;
+use crate::message::ResponseCode;
+use crate::name::Name;
+use crate::record::RData;
+use crate::rrset::RRSet;
+use crate::{RRClass, RRType, ResourceRecord};
+use std::collections::{BTreeMap, HashSet};
+use std::ops::Bound;
+
+/// The authoritative data for a zone, held as RRsets indexed by owner name. Names are kept in
+/// canonical order (RFC 4034 section 6.1) so a name is always followed by its subdomains.
+#[derive(Clone, Debug)]
+pub struct Zone {
+    origin: Name,
+    class: RRClass<u16>,
+    nodes: BTreeMap<Name, Vec<RRSet>>,
+}
+
+/// How a query was answered by [`Zone::lookup`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum LookupKind {
+    /// The records asked for were found, or a CNAME chain leads out of the zone
+    Answer,
+    /// The name is at or below a delegation, the authority section holds the NS records of the
+    /// child zone and the additional section any glue
+    Referral,
+    /// The name exists but has no records of the type asked for
+    NoData,
+    /// The name does not exist
+    NXDomain,
+    /// The name is not in this zone
+    NotAuthoritative,
+}
+
+/// The result of looking up a name in a zone, split into the sections of a response
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lookup {
+    pub kind: LookupKind,
+    pub answers: Vec<RRSet>,
+    pub authorities: Vec<RRSet>,
+    pub additionals: Vec<RRSet>,
+}
+
+impl Lookup {
+    fn new(kind: LookupKind) -> Self {
+        Lookup {
+            kind,
+            answers: Vec::new(),
+            authorities: Vec::new(),
+            additionals: Vec::new(),
+        }
+    }
+
+    /// Whether the answer comes from this zone's authoritative data, setting the AA bit
+    pub fn is_authoritative(&self) -> bool {
+        matches!(
+            self.kind,
+            LookupKind::Answer | LookupKind::NoData | LookupKind::NXDomain
+        )
+    }
+
+    /// The RCODE of a response carrying this result
+    pub fn response_code(&self) -> ResponseCode {
+        match self.kind {
+            LookupKind::NXDomain => ResponseCode::NXDOMAIN,
+            LookupKind::NotAuthoritative => ResponseCode::REFUSED,
+            _ => ResponseCode::NOERROR,
+        }
+    }
+}
+
+/// The longest CNAME chain which will be followed within a zone
+const MAX_CNAME_CHAIN: usize = 16;
+
+impl Zone {
+    pub fn new(origin: Name, class: RRClass<u16>) -> Self {
+        Zone {
+            origin,
+            class,
+            nodes: BTreeMap::new(),
+        }
+    }
+
+    /// Builds a zone from a list of records, such as those loaded from a master file
+    pub fn from_records(
+        origin: Name,
+        class: RRClass<u16>,
+        records: impl IntoIterator<Item = ResourceRecord>,
+    ) -> Result<Self, RDNSError> {
+        let mut zone = Zone::new(origin, class);
+        for record in records {
+            zone.insert(record)?;
+        }
+
+        Ok(zone)
+    }
+
+    /// Adds a record to the RRset for its owner name and type. Records must be in the zone's class
+    /// and at or below its origin.
+    pub fn insert(&mut self, record: ResourceRecord) -> Result<(), RDNSError> {
+        if record.class != self.class || !record.name.is_subdomain_of(&self.origin) {
+            return Err(RDNSError::RecordOutOfZone(format!(
+                "{} {} for record {}",
+                self.origin, self.class, record
+            )));
+        }
+
+        let sets = self.nodes.entry(record.name.clone()).or_default();
+        match sets.iter_mut().find(|set| set.contains_record(&record)) {
+            Some(set) => {
+                set.add(record)?;
+            }
+            None => sets.push(RRSet::new(record)),
+        }
+
+        Ok(())
+    }
+
+    pub fn origin(&self) -> &Name {
+        &self.origin
+    }
+
+    pub fn class(&self) -> &RRClass<u16> {
+        &self.class
+    }
+
+    /// The RRset with the given owner name and type, without following CNAMEs or delegations
+    pub fn get(&self, name: &Name, rr_type: &RRType<u16>) -> Option<&RRSet> {
+        self.nodes
+            .get(name)
+            .and_then(|sets| sets.iter().find(|set| set.rr_type() == *rr_type))
+    }
+
+    /// The SOA RRset at the apex of the zone
+    pub fn soa(&self) -> Option<&RRSet> {
+        self.get(&self.origin, &RRType::SOA)
+    }
+
+    /// All of the RRsets in the zone, in canonical order of their owner names
+    pub fn rrsets(&self) -> impl Iterator<Item = &RRSet> {
+        self.nodes.values().flatten()
+    }
+
+    /// Whether a name exists in the zone, either because it owns records or because it is an
+    /// empty non-terminal with records below it
+    pub fn contains_name(&self, name: &Name) -> bool {
+        if self.nodes.contains_key(name) {
+            return true;
+        }
+
+        self.nodes
+            .range((Bound::Excluded(name), Bound::Unbounded))
+            .next()
+            .map(|(next, _)| next.is_subdomain_of(name))
+            .unwrap_or(false)
+    }
+
+    /// Answers a query using the algorithm from RFC 1034 section 4.3.2, following CNAMEs within
+    /// the zone, stopping at delegations and synthesising answers from wildcards.
+    pub fn lookup(&self, name: &Name, rr_type: &RRType<u16>) -> Lookup {
+        if !name.is_subdomain_of(&self.origin) {
+            return Lookup::new(LookupKind::NotAuthoritative);
+        }
+
+        let mut answers = Vec::new();
+        let mut visited = HashSet::new();
+        let mut current = name.clone();
+
+        loop {
+            if !current.is_subdomain_of(&self.origin)
+                || !visited.insert(current.clone())
+                || visited.len() > MAX_CNAME_CHAIN
+            {
+                // The chain leaves the zone or loops, the rest is up to the resolver
+                let mut result = Lookup::new(LookupKind::Answer);
+                result.answers = answers;
+                return result;
+            }
+
+            if let Some(mut result) = self.referral(&current) {
+                result.answers = answers;
+                return result;
+            }
+
+            let sets = match self.nodes.get(&current) {
+                Some(sets) => sets.clone(),
+                None if self.contains_name(&current) => Vec::new(),
+                None => match self.wildcard(&current) {
+                    Some(sets) => sets
+                        .iter()
+                        .map(|set| set.with_name(current.clone()))
+                        .collect(),
+                    None => {
+                        let mut result = self.negative(LookupKind::NXDomain);
+                        result.answers = answers;
+                        return result;
+                    }
+                },
+            };
+
+            if let Some(set) = sets.iter().find(|set| set.rr_type() == *rr_type) {
+                answers.push(set.clone());
+
+                let mut result = Lookup::new(LookupKind::Answer);
+                result.answers = answers;
+                return result;
+            }
+
+            match sets.iter().find(|set| set.rr_type() == RRType::CNAME) {
+                Some(cname) => {
+                    answers.push(cname.clone());
+                    current = match &cname.rdata()[0] {
+                        RData::CNAME(target) => target.0.clone(),
+                        _ => unreachable!("a CNAME RRset only holds CNAME data"),
+                    };
+                }
+                None => {
+                    let mut result = self.negative(LookupKind::NoData);
+                    result.answers = answers;
+                    return result;
+                }
+            }
+        }
+    }
+
+    /// A referral to the highest zone cut at or above `name`, if there is one below the origin
+    fn referral(&self, name: &Name) -> Option<Lookup> {
+        let mut ancestors = Vec::new();
+        let mut current = name.clone();
+        while current != self.origin {
+            ancestors.push(current.clone());
+            current = current.parent()?;
+        }
+
+        let ns = ancestors
+            .iter()
+            .rev()
+            .find_map(|ancestor| self.get(ancestor, &RRType::NS))?;
+
+        let mut result = Lookup::new(LookupKind::Referral);
+        result.authorities.push(ns.clone());
+        for rdata in ns.rdata() {
+            if let RData::NS(target) = rdata {
+                for rr_type in [RRType::A, RRType::AAAA] {
+                    if let Some(glue) = self.get(&target.0, &rr_type) {
+                        result.additionals.push(glue.clone());
+                    }
+                }
+            }
+        }
+
+        Some(result)
+    }
+
+    /// The RRsets of the wildcard which matches `name`, which must not exist in the zone. The
+    /// wildcard is the `*` label below the closest encloser, the nearest ancestor which does.
+    fn wildcard(&self, name: &Name) -> Option<&Vec<RRSet>> {
+        let mut closest_encloser = name.parent()?;
+        while closest_encloser != self.origin && !self.contains_name(&closest_encloser) {
+            closest_encloser = closest_encloser.parent()?;
+        }
+
+        let source = closest_encloser.prepend_label(b"*").ok()?;
+        self.nodes.get(&source)
+    }
+
+    /// A negative answer with the zone's SOA in the authority section. Its TTL is the lower of the
+    /// SOA's own TTL and the MINIMUM field (RFC 2308 section 3).
+    fn negative(&self, kind: LookupKind) -> Lookup {
+        let mut result = Lookup::new(kind);
+
+        if let Some(soa) = self.soa() {
+            // The MINIMUM field is unsigned, so a value above the largest TTL caps at that TTL
+            let ttl = match &soa.rdata()[0] {
+                RData::SOA(data) => soa
+                    .ttl()
+                    .min(i32::try_from(data.minimum).unwrap_or(i32::MAX)),
+                _ => soa.ttl(),
+            };
+            result.authorities.push(soa.with_ttl(ttl));
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::RDNSError;
+    use crate::message::ResponseCode;
+    use crate::record::{RData, SOAResourceData};
+    use crate::test::{a_record, cname_record, mx_record, name, ns_record};
+    use crate::zone::{LookupKind, Zone};
+    use crate::{RRClass, RRType, ResourceRecord};
+
+    #[test]
+    fn exact_match() {
+        let zone = test_zone();
+
+        let result = zone.lookup(&name("www.example.com."), &RRType::A);
+        assert_eq!(LookupKind::Answer, result.kind);
+        assert!(result.is_authoritative());
+        assert_eq!(ResponseCode::NOERROR, result.response_code());
+        assert_eq!(1, result.answers.len());
+        assert_eq!(
+            vec![a_record("www.example.com.", 300, 1)],
+            result.answers[0].records()
+        );
+        assert!(result.authorities.is_empty());
+    }
+
+    #[test]
+    fn exact_match_ignores_case() {
+        let zone = test_zone();
+
+        let result = zone.lookup(&name("WWW.Example.COM."), &RRType::A);
+        assert_eq!(LookupKind::Answer, result.kind);
+    }
+
+    #[test]
+    fn follow_cname_within_zone() {
+        let zone = test_zone();
+
+        let result = zone.lookup(&name("alias.example.com."), &RRType::A);
+        assert_eq!(LookupKind::Answer, result.kind);
+        assert_eq!(2, result.answers.len());
+        assert_eq!(RRType::CNAME, result.answers[0].rr_type());
+        assert_eq!(RRType::A, result.answers[1].rr_type());
+        assert_eq!("www.example.com.", result.answers[1].name().to_string());
+    }
+
+    #[test]
+    fn cname_asked_for_directly() {
+        let zone = test_zone();
+
+        let result = zone.lookup(&name("alias.example.com."), &RRType::CNAME);
+        assert_eq!(LookupKind::Answer, result.kind);
+        assert_eq!(1, result.answers.len());
+        assert_eq!(RRType::CNAME, result.answers[0].rr_type());
+    }
+
+    #[test]
+    fn cname_out_of_zone() {
+        let zone = test_zone();
+
+        let result = zone.lookup(&name("external.example.com."), &RRType::A);
+        assert_eq!(LookupKind::Answer, result.kind);
+        assert_eq!(1, result.answers.len());
+        assert_eq!(RRType::CNAME, result.answers[0].rr_type());
+    }
+
+    #[test]
+    fn cname_to_missing_name() {
+        let zone = test_zone();
+
+        let result = zone.lookup(&name("dangling.example.com."), &RRType::A);
+        assert_eq!(LookupKind::NXDomain, result.kind);
+        assert_eq!(ResponseCode::NXDOMAIN, result.response_code());
+        assert_eq!(1, result.answers.len());
+        assert_eq!(RRType::SOA, result.authorities[0].rr_type());
+    }
+
+    #[test]
+    fn cname_loop() {
+        let mut zone = test_zone();
+        zone.insert(cname_record("loop1.example.com.", "loop2.example.com."))
+            .unwrap();
+        zone.insert(cname_record("loop2.example.com.", "loop1.example.com."))
+            .unwrap();
+
+        let result = zone.lookup(&name("loop1.example.com."), &RRType::A);
+        assert_eq!(LookupKind::Answer, result.kind);
+        assert_eq!(2, result.answers.len());
+    }
+
+    #[test]
+    fn delegation_with_glue() {
+        let zone = test_zone();
+
+        let result = zone.lookup(&name("www.sub.example.com."), &RRType::A);
+        assert_eq!(LookupKind::Referral, result.kind);
+        assert!(!result.is_authoritative());
+        assert!(result.answers.is_empty());
+        assert_eq!(1, result.authorities.len());
+        assert_eq!(RRType::NS, result.authorities[0].rr_type());
+        assert_eq!("sub.example.com.", result.authorities[0].name().to_string());
+        assert_eq!(1, result.additionals.len());
+        assert_eq!(
+            vec![a_record("ns1.sub.example.com.", 300, 53)],
+            result.additionals[0].records()
+        );
+    }
+
+    #[test]
+    fn delegation_point_itself() {
+        let zone = test_zone();
+
+        let result = zone.lookup(&name("sub.example.com."), &RRType::NS);
+        assert_eq!(LookupKind::Referral, result.kind);
+    }
+
+    #[test]
+    fn glue_is_not_answered_authoritatively() {
+        let zone = test_zone();
+
+        let result = zone.lookup(&name("ns1.sub.example.com."), &RRType::A);
+        assert_eq!(LookupKind::Referral, result.kind);
+    }
+
+    #[test]
+    fn wildcard_synthesis() {
+        let zone = test_zone();
+
+        let result = zone.lookup(&name("anything.wild.example.com."), &RRType::A);
+        assert_eq!(LookupKind::Answer, result.kind);
+        assert_eq!(
+            vec![a_record("anything.wild.example.com.", 300, 9)],
+            result.answers[0].records()
+        );
+
+        let result = zone.lookup(&name("deeper.anything.wild.example.com."), &RRType::A);
+        assert_eq!(LookupKind::Answer, result.kind);
+        assert_eq!(
+            "deeper.anything.wild.example.com.",
+            result.answers[0].name().to_string()
+        );
+    }
+
+    #[test]
+    fn wildcard_without_matching_type() {
+        let zone = test_zone();
+
+        let result = zone.lookup(&name("anything.wild.example.com."), &RRType::MX);
+        assert_eq!(LookupKind::NoData, result.kind);
+    }
+
+    #[test]
+    fn existing_names_block_wildcard() {
+        let zone = test_zone();
+
+        let result = zone.lookup(&name("host.wild.example.com."), &RRType::A);
+        assert_eq!(LookupKind::NoData, result.kind);
+
+        // An empty non-terminal also exists and blocks the wildcard
+        let result = zone.lookup(&name("a.b.wild.example.com."), &RRType::A);
+        assert_eq!(LookupKind::Answer, result.kind);
+        let result = zone.lookup(&name("x.c.b.wild.example.com."), &RRType::A);
+        assert_eq!(LookupKind::NXDomain, result.kind);
+    }
+
+    #[test]
+    fn no_data() {
+        let zone = test_zone();
+
+        let result = zone.lookup(&name("www.example.com."), &RRType::MX);
+        assert_eq!(LookupKind::NoData, result.kind);
+        assert!(result.is_authoritative());
+        assert_eq!(ResponseCode::NOERROR, result.response_code());
+        assert!(result.answers.is_empty());
+        assert_eq!(1, result.authorities.len());
+        assert_eq!(RRType::SOA, result.authorities[0].rr_type());
+    }
+
+    #[test]
+    fn empty_non_terminal_is_no_data() {
+        let zone = test_zone();
+
+        let result = zone.lookup(&name("b.wild.example.com."), &RRType::A);
+        assert_eq!(LookupKind::NoData, result.kind);
+    }
+
+    #[test]
+    fn nxdomain() {
+        let zone = test_zone();
+
+        let result = zone.lookup(&name("missing.example.com."), &RRType::A);
+        assert_eq!(LookupKind::NXDomain, result.kind);
+        assert_eq!(ResponseCode::NXDOMAIN, result.response_code());
+        assert!(result.answers.is_empty());
+        assert_eq!(RRType::SOA, result.authorities[0].rr_type());
+    }
+
+    #[test]
+    fn negative_answer_ttl_is_soa_minimum() {
+        let zone = test_zone();
+
+        let result = zone.lookup(&name("missing.example.com."), &RRType::A);
+        assert_eq!(60, result.authorities[0].ttl());
+    }
+
+    #[test]
+    fn negative_answer_ttl_with_soa_minimum_above_largest_ttl() {
+        let soa = ResourceRecord {
+            name: name("example.com."),
+            class: RRClass::IN,
+            ttl: 3600,
+            rdata: RData::SOA(SOAResourceData {
+                primary_name: name("ns1.example.com."),
+                responsible_name: name("admin.example.com."),
+                serial: 1,
+                refresh: 7200,
+                retry: 600,
+                expire: 3600000,
+                minimum: u32::MAX,
+            }),
+        };
+        let zone = Zone::from_records(name("example.com."), RRClass::IN, vec![soa]).unwrap();
+
+        let result = zone.lookup(&name("missing.example.com."), &RRType::A);
+        assert_eq!(3600, result.authorities[0].ttl());
+    }
+
+    #[test]
+    fn name_outside_zone() {
+        let zone = test_zone();
+
+        let result = zone.lookup(&name("www.example.org."), &RRType::A);
+        assert_eq!(LookupKind::NotAuthoritative, result.kind);
+        assert_eq!(ResponseCode::REFUSED, result.response_code());
+    }
+
+    #[test]
+    fn apex_records() {
+        let zone = test_zone();
+
+        let result = zone.lookup(&name("example.com."), &RRType::MX);
+        assert_eq!(LookupKind::Answer, result.kind);
+
+        let result = zone.lookup(&name("example.com."), &RRType::NS);
+        assert_eq!(LookupKind::Answer, result.kind);
+        assert!(zone.soa().is_some());
+    }
+
+    #[test]
+    fn insert_record_outside_zone() {
+        let mut zone = test_zone();
+
+        let err = zone
+            .insert(a_record("www.example.org.", 300, 1))
+            .unwrap_err();
+        assert!(matches!(err, RDNSError::RecordOutOfZone(_)));
+
+        let mut record = a_record("www.example.com.", 300, 1);
+        record.class = RRClass::CH;
+        let err = zone.insert(record).unwrap_err();
+        assert!(matches!(err, RDNSError::RecordOutOfZone(_)));
+    }
+
+    #[test]
+    fn records_grouped_into_sets() {
+        let mut zone = test_zone();
+        zone.insert(a_record("www.example.com.", 300, 2)).unwrap();
+        zone.insert(a_record("www.example.com.", 300, 2)).unwrap();
+
+        let set = zone.get(&name("www.example.com."), &RRType::A).unwrap();
+        assert_eq!(2, set.len());
+    }
+
+    fn test_zone() -> Zone {
+        Zone::from_records(
+            name("example.com."),
+            RRClass::IN,
+            vec![
+                ResourceRecord {
+                    name: name("example.com."),
+                    class: RRClass::IN,
+                    ttl: 3600,
+                    rdata: RData::SOA(SOAResourceData {
+                        primary_name: name("ns1.example.com."),
+                        responsible_name: name("admin.example.com."),
+                        serial: 1,
+                        refresh: 7200,
+                        retry: 600,
+                        expire: 3600000,
+                        minimum: 60,
+                    }),
+                },
+                ns_record("example.com.", "ns1.example.com."),
+                mx_record("example.com."),
+                a_record("ns1.example.com.", 300, 2),
+                a_record("www.example.com.", 300, 1),
+                cname_record("alias.example.com.", "www.example.com."),
+                cname_record("external.example.com.", "www.example.org."),
+                cname_record("dangling.example.com.", "nowhere.example.com."),
+                ns_record("sub.example.com.", "ns1.sub.example.com."),
+                a_record("ns1.sub.example.com.", 300, 53),
+                a_record("*.wild.example.com.", 300, 9),
+                mx_record("host.wild.example.com."),
+                a_record("a.b.wild.example.com.", 300, 11),
+            ],
+        )
+        .unwrap()
+    }
+}
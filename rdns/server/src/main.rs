@@ -1,15 +1,48 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::Parser;
-use rdns_config::load_txt_config;
+use rdns_config::{load_txt_zone, validate_zone, Severity};
+use tracing_subscriber::EnvFilter;
 
 mod cli;
 
 fn main() -> Result<()> {
     let cli = cli::Cli::parse();
 
+    // Parser diagnostics are off unless asked for with RUST_LOG, such as RUST_LOG=rdns_config=debug
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::from_default_env())
+        .with_writer(std::io::stderr)
+        .init();
+
     match cli.command {
         cli::Commands::Check { zone_file } => {
-            load_txt_config(zone_file)?;
+            let zone = load_txt_zone(&zone_file)?;
+
+            let mut diagnostics = zone.diagnostics;
+            diagnostics.extend(validate_zone(&zone.entries));
+            diagnostics.sort_by_key(|d| {
+                (
+                    d.file.clone(),
+                    d.position.as_ref().map(|p| (p.line, p.char)),
+                )
+            });
+
+            for diagnostic in &diagnostics {
+                // Diagnostics about the zone as a whole are not tied to any one file
+                if diagnostic.file.is_some() {
+                    eprintln!("{}", diagnostic);
+                } else {
+                    eprintln!("{}: {}", zone_file.display(), diagnostic);
+                }
+            }
+
+            let errors = diagnostics
+                .iter()
+                .filter(|d| d.severity == Severity::Error)
+                .count();
+            if errors > 0 {
+                bail!("{} has {} error(s)", zone_file.display(), errors);
+            }
         }
     }
 